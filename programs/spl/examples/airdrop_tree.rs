@@ -0,0 +1,43 @@
+//! Builds an airdrop merkle tree with the same `MerkleTree` the program's unit tests verify,
+//! so the integration tests never re-implement the leaf or node hashing:
+//!
+//!     cargo run --example airdrop_tree -- <claimant>:<amount> [<claimant>:<amount> ...]
+//!
+//! A recipient's index is its position on the command line. Prints the root and one proof
+//! per recipient as JSON.
+
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use spl::MerkleTree;
+
+fn main() {
+    let allocations: Vec<(Pubkey, u64)> = std::env
+        ::args()
+        .skip(1)
+        .map(|arg| {
+            let (claimant, amount) = arg.split_once(':').expect("expected <claimant>:<amount>");
+            (
+                Pubkey::from_str(claimant).expect("claimant pubkey"),
+                amount.parse().expect("amount"),
+            )
+        })
+        .collect();
+    let tree = MerkleTree::from_allocations(&allocations);
+
+    let proofs: Vec<String> = (0..allocations.len())
+        .map(|index| {
+            let nodes: Vec<String> = tree
+                .proof(index)
+                .iter()
+                .map(|node| format!("{:?}", node.to_vec()))
+                .collect();
+            format!("[{}]", nodes.join(", "))
+        })
+        .collect();
+
+    println!("{{");
+    println!("  \"root\": {:?},", tree.root().to_vec());
+    println!("  \"proofs\": [{}]", proofs.join(", "));
+    println!("}}");
+}
@@ -0,0 +1,62 @@
+//! Generates the pre-built proof data used by the confidential transfer tests.
+//!
+//! The proofs only depend on the ElGamal keypair and on ciphertexts that Token-2022 derives
+//! deterministically (configure, deposit and apply never add randomness), so they can be
+//! produced once and replayed against any freshly created mint and token account:
+//!
+//!     cargo run --example confidential_fixtures > tests/fixtures/confidential.json
+
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::confidential_transfer::instruction::{
+        verify_pubkey_validity,
+        verify_withdraw,
+        PubkeyValidityData,
+        WithdrawData,
+    },
+    solana_zk_token_sdk::{
+        encryption::{ auth_encryption::AeKey, elgamal::{ ElGamalCiphertext, ElGamalKeypair } },
+        zk_token_elgamal::{ ops, pod },
+    },
+};
+
+const DEPOSIT_AMOUNT: u64 = 100;
+const WITHDRAW_AMOUNT: u64 = 40;
+
+fn main() {
+    let owner = ElGamalKeypair::new_rand();
+    let auditor = ElGamalKeypair::new_rand();
+    let ae_key = AeKey::new_rand();
+
+    let pubkey_validity = PubkeyValidityData::new(&owner).expect("pubkey validity proof");
+
+    // Mirror Token-2022: configure zeroes the available balance, deposit adds the
+    // low 16 bits to the pending balance and apply folds pending into available
+    let zero = pod::ElGamalCiphertext::default();
+    let pending_lo = ops::add_to(&zero, DEPOSIT_AMOUNT & u64::from(u16::MAX)).expect("pending lo");
+    let pending_hi = ops::add_to(&zero, DEPOSIT_AMOUNT >> 16).expect("pending hi");
+    let available = ops::add_with_lo_hi(&zero, &pending_lo, &pending_hi).expect("available");
+    let available: ElGamalCiphertext = available.try_into().expect("available ciphertext");
+
+    let withdraw = WithdrawData::new(WITHDRAW_AMOUNT, &owner, DEPOSIT_AMOUNT, &available).expect(
+        "withdraw proof"
+    );
+
+    let decryptable = |amount: u64| pod::AeCiphertext::from(ae_key.encrypt(amount)).0.to_vec();
+
+    println!("{{");
+    println!("  \"auditorElgamalPubkey\": {:?},", auditor.pubkey().to_bytes().to_vec());
+    println!("  \"depositAmount\": {},", DEPOSIT_AMOUNT);
+    println!("  \"withdrawAmount\": {},", WITHDRAW_AMOUNT);
+    println!("  \"decryptableZeroBalance\": {:?},", decryptable(0));
+    println!("  \"decryptableAfterDeposit\": {:?},", decryptable(DEPOSIT_AMOUNT));
+    println!(
+        "  \"decryptableAfterWithdraw\": {:?},",
+        decryptable(DEPOSIT_AMOUNT - WITHDRAW_AMOUNT)
+    );
+    println!(
+        "  \"pubkeyValidityProofIx\": {:?},",
+        verify_pubkey_validity(None, &pubkey_validity).data
+    );
+    println!("  \"withdrawProofIx\": {:?}", verify_withdraw(None, &withdraw).data);
+    println!("}}");
+}
@@ -0,0 +1,156 @@
+use anchor_lang::{ prelude::*, solana_program::program::invoke };
+use anchor_spl::{
+    token_2022::spl_token_2022::{ extension::ExtensionType, instruction::reallocate },
+    token_2022_extensions::{ memo_transfer_disable, memo_transfer_initialize, MemoTransfer },
+    token_interface::{ Token2022, TokenAccount },
+};
+
+use crate::utils::*;
+
+/// Token-account extensions a holder can add after the account was created
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AccountExtension {
+    /// Incoming transfers must be preceded by a memo
+    MemoTransfer,
+    /// Owner-signed transfers, burns, approvals and closes are blocked inside CPIs.
+    /// Token-2022 only accepts toggling it in a top-level instruction to the token program.
+    CpiGuard,
+}
+
+impl From<AccountExtension> for ExtensionType {
+    fn from(extension: AccountExtension) -> Self {
+        match extension {
+            AccountExtension::MemoTransfer => ExtensionType::MemoTransfer,
+            AccountExtension::CpiGuard => ExtensionType::CpiGuard,
+        }
+    }
+}
+
+/// TOKEN ACCOUNT EXTENSION FUNCTIONS
+// Grow a token account so it can hold the given extensions
+pub fn reallocate_spl_token_account(
+    ctx: Context<ManageTokenAccount>,
+    extensions: Vec<AccountExtension>
+) -> Result<()> {
+    require!(!extensions.is_empty(), AccountExtensionError::NoExtensions);
+
+    let extension_types: Vec<ExtensionType> = extensions
+        .iter()
+        .map(|extension| (*extension).into())
+        .collect();
+    reallocate_for_extensions(
+        &ctx.accounts.token_program,
+        &ctx.accounts.token_account.to_account_info(),
+        &ctx.accounts.owner,
+        &ctx.accounts.system_program,
+        &extension_types
+    )?;
+
+    emit!(TokenAccountReallocated {
+        token_account: ctx.accounts.token_account.key(),
+        owner: ctx.accounts.owner.key(),
+        extensions,
+    });
+    Ok(())
+}
+
+// Require a memo on every incoming transfer, adding the extension if needed
+pub fn enable_required_memo_transfers(ctx: Context<ManageTokenAccount>) -> Result<()> {
+    reallocate_for_extensions(
+        &ctx.accounts.token_program,
+        &ctx.accounts.token_account.to_account_info(),
+        &ctx.accounts.owner,
+        &ctx.accounts.system_program,
+        &[ExtensionType::MemoTransfer]
+    )?;
+    memo_transfer_initialize(ctx.accounts.memo_transfer_ctx())?;
+
+    emit!(MemoTransferToggled {
+        token_account: ctx.accounts.token_account.key(),
+        enabled: true,
+    });
+    Ok(())
+}
+
+// Stop requiring memos on incoming transfers
+pub fn disable_required_memo_transfers(ctx: Context<ManageTokenAccount>) -> Result<()> {
+    memo_transfer_disable(ctx.accounts.memo_transfer_ctx())?;
+
+    emit!(MemoTransferToggled {
+        token_account: ctx.accounts.token_account.key(),
+        enabled: false,
+    });
+    Ok(())
+}
+
+// Reallocate through Token-2022 (a no-op when the extensions already fit) and keep the account rent-exempt
+pub fn reallocate_for_extensions<'info>(
+    token_program: &Program<'info, Token2022>,
+    token_account: &AccountInfo<'info>,
+    owner: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    extension_types: &[ExtensionType]
+) -> Result<()> {
+    let ix = reallocate(
+        token_program.key,
+        token_account.key,
+        owner.key,
+        owner.key,
+        &[],
+        extension_types
+    )?;
+    invoke(
+        &ix,
+        &[
+            token_account.clone(),
+            owner.to_account_info(),
+            system_program.to_account_info(),
+        ]
+    )?;
+    update_account_lamports_to_minimum_balance(
+        token_account.clone(),
+        owner.to_account_info(),
+        system_program.to_account_info()
+    )
+}
+
+impl<'info> ManageTokenAccount<'info> {
+    fn memo_transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, MemoTransfer<'info>> {
+        CpiContext::new(self.token_program.to_account_info(), MemoTransfer {
+            token_program_id: self.token_program.to_account_info(),
+            account: self.token_account.to_account_info(),
+            owner: self.owner.to_account_info(),
+        })
+    }
+}
+
+/// TOKEN ACCOUNT EXTENSION ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct ManageTokenAccount<'info> {
+    /// Owner of the token account (also pays for any reallocation)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut, token::authority = owner)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event]
+pub struct TokenAccountReallocated {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub extensions: Vec<AccountExtension>,
+}
+
+#[event]
+pub struct MemoTransferToggled {
+    pub token_account: Pubkey,
+    pub enabled: bool,
+}
+
+#[error_code]
+pub enum AccountExtensionError {
+    #[msg("at least one extension must be requested")]
+    NoExtensions,
+}
@@ -0,0 +1,341 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ Mint, TokenAccount, TokenInterface, TransferChecked },
+};
+
+use crate::{ state::*, utils::* };
+
+/// AIRDROP FUNCTIONS
+// Fund a merkle distributor whose root commits to every (index, claimant, amount) allocation
+pub fn create_merkle_distributor<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateDistributor<'info>>,
+    id: u64,
+    merkle_root: [u8; 32],
+    num_recipients: u32,
+    total_amount: u64,
+    start_time: i64,
+    end_time: i64
+) -> Result<()> {
+    require!(total_amount > 0, AirdropError::ZeroAmount);
+    require!(
+        num_recipients > 0 && num_recipients <= MAX_AIRDROP_RECIPIENTS,
+        AirdropError::InvalidRecipientCount
+    );
+    require!(start_time < end_time, AirdropError::InvalidClaimWindow);
+    require!(end_time > Clock::get()?.unix_timestamp, AirdropError::InvalidClaimWindow);
+
+    // Transfer the airdrop supply from the admin into the vault
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.admin_token_account.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.admin.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts
+    ).with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(cpi_ctx, total_amount, ctx.accounts.mint.decimals)?;
+
+    // Record what actually arrived (transfer-fee mints withhold part of the transfer)
+    ctx.accounts.vault.reload()?;
+    let total_amount = ctx.accounts.vault.amount;
+
+    let distributor = &mut ctx.accounts.distributor;
+    distributor.admin = ctx.accounts.admin.key();
+    distributor.mint = ctx.accounts.mint.key();
+    distributor.vault = ctx.accounts.vault.key();
+    distributor.id = id;
+    distributor.merkle_root = merkle_root;
+    distributor.num_recipients = num_recipients;
+    distributor.total_amount = total_amount;
+    distributor.claimed_amount = 0;
+    distributor.num_claimed = 0;
+    distributor.start_time = start_time;
+    distributor.end_time = end_time;
+    distributor.clawed_back = false;
+    distributor.bump = ctx.bumps.distributor;
+    distributor.claimed_bitmap = vec![0; Distributor::bitmap_len(num_recipients)];
+
+    emit!(DistributorCreated {
+        distributor: distributor.key(),
+        mint: distributor.mint,
+        merkle_root,
+        num_recipients,
+        total_amount,
+        start_time,
+        end_time,
+    });
+    Ok(())
+}
+
+// Claim an allocation by proving it is part of the distributor's merkle root
+pub fn claim_airdrop_allocation<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimAirdrop<'info>>,
+    index: u32,
+    amount: u64,
+    proof: Vec<[u8; 32]>
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let distributor = &ctx.accounts.distributor;
+    require!(
+        now >= distributor.start_time && now < distributor.end_time,
+        AirdropError::ClaimWindowClosed
+    );
+    require!(index < distributor.num_recipients, AirdropError::InvalidIndex);
+    require!(!distributor.is_claimed(index), AirdropError::AlreadyClaimed);
+
+    let leaf = airdrop_leaf(index, &ctx.accounts.claimant.key(), amount);
+    require!(
+        verify_merkle_proof(&proof, &distributor.merkle_root, leaf),
+        AirdropError::InvalidProof
+    );
+
+    transfer_from_vault(
+        &ctx.accounts.distributor,
+        &ctx.accounts.vault,
+        ctx.accounts.claimant_token_account.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        amount
+    )?;
+
+    let distributor = &mut ctx.accounts.distributor;
+    distributor.set_claimed(index);
+    distributor.claimed_amount = distributor.claimed_amount
+        .checked_add(amount)
+        .ok_or(AirdropError::MathOverflow)?;
+    distributor.num_claimed = distributor.num_claimed
+        .checked_add(1)
+        .ok_or(AirdropError::MathOverflow)?;
+
+    emit!(AirdropClaimed {
+        distributor: distributor.key(),
+        claimant: ctx.accounts.claimant.key(),
+        index,
+        amount,
+        time: now,
+    });
+    Ok(())
+}
+
+// Return everything left in the vault to the admin once the claim window has ended
+pub fn clawback_unclaimed_airdrop<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClawbackAirdrop<'info>>
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let distributor = &ctx.accounts.distributor;
+    require!(now >= distributor.end_time, AirdropError::ClaimWindowOpen);
+    require!(!distributor.clawed_back, AirdropError::AlreadyClawedBack);
+
+    let amount = ctx.accounts.vault.amount;
+    if amount > 0 {
+        transfer_from_vault(
+            &ctx.accounts.distributor,
+            &ctx.accounts.vault,
+            ctx.accounts.admin_token_account.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+            amount
+        )?;
+    }
+
+    let distributor = &mut ctx.accounts.distributor;
+    distributor.clawed_back = true;
+
+    emit!(AirdropClawedBack {
+        distributor: distributor.key(),
+        admin: distributor.admin,
+        amount,
+        time: now,
+    });
+    Ok(())
+}
+
+impl Distributor {
+    // Bytes needed for one claimed bit per recipient
+    pub fn bitmap_len(num_recipients: u32) -> usize {
+        (num_recipients as usize).div_ceil(8)
+    }
+
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.claimed_bitmap[(index / 8) as usize] & (1 << (index % 8)) != 0
+    }
+
+    fn set_claimed(&mut self, index: u32) {
+        self.claimed_bitmap[(index / 8) as usize] |= 1 << (index % 8);
+    }
+}
+
+// Transfer out of a distributor vault, signed by the distributor PDA (`hook_accounts` are the
+// transfer hook's extra accounts for hooked mints)
+fn transfer_from_vault<'info>(
+    distributor: &Account<'info, Distributor>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    hook_accounts: &[AccountInfo<'info>],
+    amount: u64
+) -> Result<()> {
+    let id_bytes = distributor.id.to_le_bytes();
+    let seeds: &[&[u8]] = &[
+        DISTRIBUTOR_SEED,
+        distributor.mint.as_ref(),
+        distributor.admin.as_ref(),
+        &id_bytes,
+        &[distributor.bump],
+    ];
+    let signer_seeds = &[seeds];
+
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        to,
+        authority: distributor.to_account_info(),
+        mint: mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds
+    ).with_remaining_accounts(hook_accounts.to_vec());
+    transfer_checked_with_hook(cpi_ctx, amount, mint.decimals)
+}
+
+/// AIRDROP ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+#[instruction(id: u64, merkle_root: [u8; 32], num_recipients: u32)]
+pub struct CreateDistributor<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, token::mint = mint, token::authority = admin)]
+    pub admin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        space = Distributor::space(num_recipients),
+        seeds = [DISTRIBUTOR_SEED, mint.key().as_ref(), admin.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub distributor: Box<Account<'info, Distributor>>,
+    /// The associated token account owned by the distributor PDA that holds the airdrop supply
+    #[account(
+        init,
+        associated_token::mint = mint,
+        associated_token::authority = distributor,
+        associated_token::token_program = token_program,
+        payer = admin
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAirdrop<'info> {
+    pub claimant: Signer<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            DISTRIBUTOR_SEED,
+            mint.key().as_ref(),
+            distributor.admin.as_ref(),
+            &distributor.id.to_le_bytes(),
+        ],
+        bump = distributor.bump,
+        has_one = mint,
+        has_one = vault
+    )]
+    pub distributor: Box<Account<'info, Distributor>>,
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = mint, token::authority = claimant)]
+    pub claimant_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClawbackAirdrop<'info> {
+    pub admin: Signer<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            DISTRIBUTOR_SEED,
+            mint.key().as_ref(),
+            admin.key().as_ref(),
+            &distributor.id.to_le_bytes(),
+        ],
+        bump = distributor.bump,
+        has_one = admin,
+        has_one = mint,
+        has_one = vault
+    )]
+    pub distributor: Box<Account<'info, Distributor>>,
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = mint, token::authority = admin)]
+    pub admin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct DistributorCreated {
+    pub distributor: Pubkey,
+    pub mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub num_recipients: u32,
+    pub total_amount: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct AirdropClaimed {
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub index: u32,
+    pub amount: u64,
+    pub time: i64,
+}
+
+#[event]
+pub struct AirdropClawedBack {
+    pub distributor: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub time: i64,
+}
+
+#[error_code]
+pub enum AirdropError {
+    #[msg("amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("recipient count must be between 1 and the distributor maximum")]
+    InvalidRecipientCount,
+    #[msg("claim window must end after it starts and in the future")]
+    InvalidClaimWindow,
+    #[msg("claim window is not open")]
+    ClaimWindowClosed,
+    #[msg("claim window has not ended yet")]
+    ClaimWindowOpen,
+    #[msg("claim index is out of range")]
+    InvalidIndex,
+    #[msg("allocation has already been claimed")]
+    AlreadyClaimed,
+    #[msg("invalid merkle proof")]
+    InvalidProof,
+    #[msg("unclaimed tokens have already been clawed back")]
+    AlreadyClawedBack,
+    #[msg("math overflow")]
+    MathOverflow,
+}
@@ -1,9 +1,17 @@
-use crate::state::*;
+use crate::{ state::*, utils::* };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_2022::{ burn, mint_to, transfer_checked, Burn, MintTo, TransferChecked },
-    token_interface::{ Mint, Token2022, TokenAccount },
+    token_interface::{
+        burn,
+        mint_to,
+        Burn,
+        Mint,
+        MintTo,
+        TokenAccount,
+        TokenInterface,
+        TransferChecked,
+    },
 };
 use std::mem::size_of;
 
@@ -14,6 +22,10 @@ pub fn initialize_liquidity_pool(
     pool_fee_bps: u16
 ) -> Result<()> {
     require!(pool_fee_bps < (FEE_DENOM as u16), AmmError::InvalidFee);
+    require_transferable_pair(
+        &ctx.accounts.token_a_mint.to_account_info(),
+        &ctx.accounts.token_b_mint.to_account_info()
+    )?;
 
     let pool = &mut ctx.accounts.pool;
     pool.bump = ctx.bumps.pool;
@@ -39,12 +51,17 @@ pub fn initialize_liquidity_pool(
 // Fee denominator (10000 = 100%)
 pub const FEE_DENOM: u128 = 10_000;
 
-// Add liquidity to the pool
-pub fn add_liquidity(
-    ctx: Context<AddLiquidity>,
+// Add liquidity to the pool (hooked mints take their transfer hook accounts from
+// `remaining_accounts`, for both tokens of the pair)
+pub fn add_liquidity<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'info>>,
     amount_a_desired: u64,
     max_amount_b: u64
 ) -> Result<()> {
+    require_transferable_pair(
+        &ctx.accounts.token_a_mint.to_account_info(),
+        &ctx.accounts.token_b_mint.to_account_info()
+    )?;
     let pool = &mut ctx.accounts.pool;
     let vault_a = &ctx.accounts.vault_a;
     let vault_b = &ctx.accounts.vault_b;
@@ -63,29 +80,28 @@ pub fn add_liquidity(
         q
     };
 
-    let tp = ctx.accounts.token_program.to_account_info();
     let depositor = ctx.accounts.depositor.to_account_info();
 
     // Transfer token A from user to vault
-    transfer_checked(
-        CpiContext::new(tp.clone(), TransferChecked {
+    transfer_checked_with_hook(
+        CpiContext::new(ctx.accounts.token_a_program.to_account_info(), TransferChecked {
             from: ctx.accounts.user_token_a_account.to_account_info(),
             to: vault_a.to_account_info(),
             authority: depositor.clone(),
             mint: ctx.accounts.token_a_mint.to_account_info(),
-        }),
+        }).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
         amount_a_desired,
         ctx.accounts.token_a_mint.decimals
     )?;
 
     // Transfer token B from user to vault
-    transfer_checked(
-        CpiContext::new(tp.clone(), TransferChecked {
+    transfer_checked_with_hook(
+        CpiContext::new(ctx.accounts.token_b_program.to_account_info(), TransferChecked {
             from: ctx.accounts.user_token_b_account.to_account_info(),
             to: vault_b.to_account_info(),
             authority: depositor,
             mint: ctx.accounts.token_b_mint.to_account_info(),
-        }),
+        }).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
         amount_b_optimal,
         ctx.accounts.token_b_mint.decimals
     )?;
@@ -112,7 +128,7 @@ pub fn add_liquidity(
 
     mint_to(
         CpiContext::new_with_signer(
-            tp,
+            ctx.accounts.token_program.to_account_info(),
             MintTo {
                 mint: ctx.accounts.lp_mint.to_account_info(),
                 to: ctx.accounts.user_lp_mint_account.to_account_info(),
@@ -139,7 +155,10 @@ pub fn add_liquidity(
 }
 
 // Remove liquidity from the pool
-pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, lp_amount: u64) -> Result<()> {
+pub fn remove_liquidity<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RemoveLiquidity<'info>>,
+    lp_amount: u64
+) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
     let vault_a = &ctx.accounts.vault_a;
     let vault_b = &ctx.accounts.vault_b;
@@ -183,12 +202,11 @@ pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, lp_amount: u64) -> Result
     ];
 
     let signer_seeds: &[&[&[u8]]] = &[seeds];
-    let tp = ctx.accounts.token_program.to_account_info();
 
     // Transfer token A from vault to user
-    transfer_checked(
+    transfer_checked_with_hook(
         CpiContext::new_with_signer(
-            tp.clone(),
+            ctx.accounts.token_a_program.to_account_info(),
             TransferChecked {
                 from: vault_a.to_account_info(),
                 to: ctx.accounts.user_token_a_account.to_account_info(),
@@ -196,14 +214,14 @@ pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, lp_amount: u64) -> Result
                 mint: ctx.accounts.token_a_mint.to_account_info(),
             },
             signer_seeds
-        ),
+        ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
         amount_a as u64,
         ctx.accounts.token_a_mint.decimals
     )?;
     // Transfer token B from vault to user
-    transfer_checked(
+    transfer_checked_with_hook(
         CpiContext::new_with_signer(
-            tp,
+            ctx.accounts.token_b_program.to_account_info(),
             TransferChecked {
                 from: vault_b.to_account_info(),
                 to: ctx.accounts.user_token_b_account.to_account_info(),
@@ -211,7 +229,7 @@ pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, lp_amount: u64) -> Result
                 mint: ctx.accounts.token_b_mint.to_account_info(),
             },
             signer_seeds
-        ),
+        ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
         amount_b as u64,
         ctx.accounts.token_b_mint.decimals
     )?;
@@ -280,9 +298,16 @@ pub fn quote(ctx: Context<Quote>, amount_in: u128) -> Result<u128> {
 }
 
 // Execute token swap
-pub fn swap(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
+pub fn swap<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Swap<'info>>,
+    amount_in: u64,
+    min_out: u64
+) -> Result<()> {
+    require_transferable_pair(
+        &ctx.accounts.token_a_mint.to_account_info(),
+        &ctx.accounts.token_b_mint.to_account_info()
+    )?;
     let pool = &mut ctx.accounts.pool;
-    let tp = ctx.accounts.token_program.to_account_info();
 
     // Determine swap direction and setup variables
     let (
@@ -292,6 +317,8 @@ pub fn swap(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
         user_out,
         mint_in_ai,
         mint_out_ai,
+        program_in,
+        program_out,
         dec_in,
         dec_out,
         reserve_in,
@@ -304,6 +331,8 @@ pub fn swap(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
             &ctx.accounts.user_out,
             ctx.accounts.token_a_mint.to_account_info(),
             ctx.accounts.token_b_mint.to_account_info(),
+            ctx.accounts.token_a_program.to_account_info(),
+            ctx.accounts.token_b_program.to_account_info(),
             ctx.accounts.token_a_mint.decimals,
             ctx.accounts.token_b_mint.decimals,
             pool.reserve_a,
@@ -317,6 +346,8 @@ pub fn swap(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
             &ctx.accounts.user_out,
             ctx.accounts.token_b_mint.to_account_info(),
             ctx.accounts.token_a_mint.to_account_info(),
+            ctx.accounts.token_b_program.to_account_info(),
+            ctx.accounts.token_a_program.to_account_info(),
             ctx.accounts.token_b_mint.decimals,
             ctx.accounts.token_a_mint.decimals,
             pool.reserve_b,
@@ -337,13 +368,13 @@ pub fn swap(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
     require!(amount_out >= min_out, AmmError::SlippageExceeded);
 
     // Transfer tokens from user to vault
-    anchor_spl::token_2022::transfer_checked(
-        CpiContext::new(tp.clone(), TransferChecked {
+    transfer_checked_with_hook(
+        CpiContext::new(program_in, TransferChecked {
             from: user_in.to_account_info(),
             to: vault_in.to_account_info(),
             authority: ctx.accounts.swapper.to_account_info(),
             mint: mint_in_ai.clone(),
-        }),
+        }).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
         amount_in,
         dec_in
     )?;
@@ -355,9 +386,9 @@ pub fn swap(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
         pool.token_b_mint.as_ref(),
         &[pool.bump],
     ];
-    anchor_spl::token_2022::transfer_checked(
+    transfer_checked_with_hook(
         CpiContext::new_with_signer(
-            tp,
+            program_out,
             TransferChecked {
                 from: vault_out.to_account_info(),
                 to: user_out.to_account_info(),
@@ -365,7 +396,7 @@ pub fn swap(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
                 mint: mint_out_ai.clone(),
             },
             &[seeds]
-        ),
+        ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
         amount_out,
         dec_out
     )?;
@@ -398,6 +429,15 @@ fn checked_div(a: u128, b: u128) -> Result<u128> {
     a.checked_div(b).ok_or(AmmError::MathOverflow.into())
 }
 
+// Reject soulbound mints before any transfer CPI fails on them
+fn require_transferable_pair(mint_a: &AccountInfo, mint_b: &AccountInfo) -> Result<()> {
+    require!(
+        !is_non_transferable_mint(mint_a)? && !is_non_transferable_mint(mint_b)?,
+        AmmError::NonTransferableMint
+    );
+    Ok(())
+}
+
 // Integer square root function
 fn isqrt(n: u128) -> u64 {
     (n as f64).sqrt() as u64
@@ -408,11 +448,13 @@ fn isqrt(n: u128) -> u64 {
 pub struct LiquidityPool<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
+    #[account(mint::token_program = token_a_program)]
     pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_b_program)]
     pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
-    #[account(mut)]
+    #[account(mut, token::mint = token_a_mint, token::token_program = token_a_program)]
     pub vault_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
-    #[account(mut)]
+    #[account(mut, token::mint = token_b_mint, token::token_program = token_b_program)]
     pub vault_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         init_if_needed,
@@ -423,7 +465,12 @@ pub struct LiquidityPool<'info> {
     )]
     pub pool: Box<Account<'info, LiquidityPoolAMM>>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token2022>,
+    /// Token program owning token A (classic SPL Token or Token-2022)
+    pub token_a_program: Interface<'info, TokenInterface>,
+    /// Token program owning token B (classic SPL Token or Token-2022)
+    pub token_b_program: Interface<'info, TokenInterface>,
+    /// Token program the LP mint is created under
+    pub token_program: Interface<'info, TokenInterface>,
     #[account(
         init,
         seeds = [b"lp_mint", pool.key().as_ref()],
@@ -442,7 +489,9 @@ pub struct LiquidityPool<'info> {
 pub struct AddLiquidity<'info> {
     #[account(mut)]
     pub depositor: Signer<'info>,
+    #[account(mint::token_program = token_a_program)]
     pub token_a_mint: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = token_b_program)]
     pub token_b_mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
     pub vault_a: InterfaceAccount<'info, TokenAccount>,
@@ -454,12 +503,15 @@ pub struct AddLiquidity<'info> {
     pub user_token_a_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub user_token_b_account: InterfaceAccount<'info, TokenAccount>,
-    #[account(mut)]
+    #[account(mut, mint::token_program = token_program)]
     pub lp_mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
     pub user_lp_mint_account: InterfaceAccount<'info, TokenAccount>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token2022>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
+    /// Token program of the LP mint
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
@@ -471,7 +523,9 @@ pub struct RemoveLiquidity<'info> {
     pub vault_a: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub vault_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_a_program)]
     pub token_a_mint: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = token_b_program)]
     pub token_b_mint: InterfaceAccount<'info, Mint>,
     #[account(mut,has_one = vault_a, has_one = vault_b)]
     pub pool: Account<'info, LiquidityPoolAMM>,
@@ -479,11 +533,14 @@ pub struct RemoveLiquidity<'info> {
     pub user_token_a_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub user_token_b_account: InterfaceAccount<'info, TokenAccount>,
-    #[account(mut)]
+    #[account(mut, mint::token_program = token_program)]
     pub lp_mint: InterfaceAccount<'info, Mint>,
     #[account(mut,)]
     pub user_lp_mint_account: InterfaceAccount<'info, TokenAccount>,
-    pub token_program: Program<'info, Token2022>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
+    /// Token program of the LP mint
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
@@ -502,11 +559,12 @@ pub struct Swap<'info> {
     #[account(mut)]
     pub user_out: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(mut, mint::token_program = token_a_program)]
     pub token_a_mint: InterfaceAccount<'info, Mint>,
-    #[account(mut)]
+    #[account(mut, mint::token_program = token_b_program)]
     pub token_b_mint: InterfaceAccount<'info, Mint>,
-    pub token_program: Program<'info, Token2022>,
+    pub token_a_program: Interface<'info, TokenInterface>,
+    pub token_b_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
@@ -566,4 +624,6 @@ pub enum AmmError {
     SlippageExceeded,
     #[msg("invalid mint address in pool")]
     InvalidMint,
+    #[msg("non-transferable mints cannot be pooled")]
+    NonTransferableMint,
 }
@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::{ set_authority, spl_token_2022::instruction::AuthorityType, SetAuthority },
+    token_interface::{ Mint, Token2022, TokenAccount },
+};
+
+use crate::utils::*;
+
+/// Mint-level authorities that can be rotated or revoked
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MintAuthorityType {
+    MintTokens,
+    FreezeAccount,
+    CloseMint,
+    MetadataPointer,
+    TransferFeeConfig,
+    WithheldWithdraw,
+    InterestRate,
+    PermanentDelegate,
+    TransferHookProgramId,
+}
+
+impl From<MintAuthorityType> for AuthorityType {
+    fn from(authority_type: MintAuthorityType) -> Self {
+        match authority_type {
+            MintAuthorityType::MintTokens => AuthorityType::MintTokens,
+            MintAuthorityType::FreezeAccount => AuthorityType::FreezeAccount,
+            MintAuthorityType::CloseMint => AuthorityType::CloseMint,
+            MintAuthorityType::MetadataPointer => AuthorityType::MetadataPointer,
+            MintAuthorityType::TransferFeeConfig => AuthorityType::TransferFeeConfig,
+            MintAuthorityType::WithheldWithdraw => AuthorityType::WithheldWithdraw,
+            MintAuthorityType::InterestRate => AuthorityType::InterestRate,
+            MintAuthorityType::PermanentDelegate => AuthorityType::PermanentDelegate,
+            MintAuthorityType::TransferHookProgramId => AuthorityType::TransferHookProgramId,
+        }
+    }
+}
+
+/// AUTHORITY FUNCTIONS
+// Rotate a mint authority, or revoke it permanently with `None`
+pub fn set_spl_mint_authority(
+    ctx: Context<SetMintAuthority>,
+    authority_type: MintAuthorityType,
+    new_authority: Option<Pubkey>
+) -> Result<()> {
+    // The mint authority is a program PDA managed through the minter registry
+    require!(
+        authority_type != MintAuthorityType::MintTokens,
+        AuthorityError::ProgramMintAuthority
+    );
+
+    let cpi_accounts = SetAuthority {
+        current_authority: ctx.accounts.current_authority.to_account_info(),
+        account_or_mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    set_authority(cpi_ctx, authority_type.into(), new_authority)?;
+
+    emit!(MintAuthorityChanged {
+        mint: ctx.accounts.mint.key(),
+        authority_type,
+        old_authority: ctx.accounts.current_authority.key(),
+        new_authority,
+    });
+    Ok(())
+}
+
+// Transfer ownership of a token account to a new wallet
+pub fn set_spl_token_account_owner(ctx: Context<SetTokenAccountOwner>, new_owner: Pubkey) -> Result<()> {
+    // Associated token accounts on Token-2022 always carry ImmutableOwner
+    require!(
+        !has_immutable_owner(&ctx.accounts.account.to_account_info())?,
+        AuthorityError::ImmutableOwner
+    );
+
+    let cpi_accounts = SetAuthority {
+        current_authority: ctx.accounts.owner.to_account_info(),
+        account_or_mint: ctx.accounts.account.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    set_authority(cpi_ctx, AuthorityType::AccountOwner, Some(new_owner))?;
+
+    emit!(AccountOwnerChanged {
+        account: ctx.accounts.account.key(),
+        old_owner: ctx.accounts.owner.key(),
+        new_owner,
+    });
+    Ok(())
+}
+
+/// AUTHORITY ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct SetMintAuthority<'info> {
+    /// Must match the mint's current authority of the given type
+    pub current_authority: Signer<'info>,
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct SetTokenAccountOwner<'info> {
+    /// The current owner of `account`
+    pub owner: Signer<'info>,
+    #[account(mut, token::authority = owner)]
+    pub account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event]
+pub struct MintAuthorityChanged {
+    pub mint: Pubkey,
+    pub authority_type: MintAuthorityType,
+    pub old_authority: Pubkey,
+    pub new_authority: Option<Pubkey>,
+}
+
+#[event]
+pub struct AccountOwnerChanged {
+    pub account: Pubkey,
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[error_code]
+pub enum AuthorityError {
+    #[msg("token account has an immutable owner")]
+    ImmutableOwner,
+    #[msg("mint authority is held by the program; use the minter registry instead")]
+    ProgramMintAuthority,
+}
@@ -0,0 +1,172 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::{ mint_to, MintTo, TransferChecked },
+    token_interface::{ Mint, TokenAccount, TokenInterface },
+};
+
+use crate::{ instructions::{ check_supply_cap, consume_minter_allowance }, state::*, utils::* };
+
+/// BATCH FUNCTIONS
+// Mint to every destination in `remaining_accounts`, charging the minter once for the total
+pub fn batch_mint_spl_tokens<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchMintTokens<'info>>,
+    amounts: Vec<u64>
+) -> Result<()> {
+    let mint_key = ctx.accounts.mint.key();
+    let total_amount = batch_total(&amounts, ctx.remaining_accounts, mint_key)?;
+
+    check_supply_cap(&ctx.accounts.mint_config, ctx.accounts.mint.supply, total_amount)?;
+    consume_minter_allowance(&mut ctx.accounts.minter, total_amount)?;
+
+    // The program PDA holds the mint authority and signs on the minter's behalf
+    let seeds: &[&[u8]] = &[MINT_AUTHORITY_SEED, mint_key.as_ref(), &[ctx.bumps.mint_authority]];
+    let signer_seeds = &[seeds];
+
+    for (destination, amount) in ctx.remaining_accounts.iter().zip(amounts.iter()) {
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: destination.clone(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds
+        );
+        mint_to(cpi_ctx, *amount)?;
+    }
+
+    emit!(BatchMinted {
+        mint: mint_key,
+        minter: ctx.accounts.authority.key(),
+        recipients: amounts.len() as u32,
+        total_amount,
+        time: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Transfer from one source to every destination in `remaining_accounts`. The destinations come
+// first; any accounts after them are the transfer hook's extra accounts for hooked mints.
+pub fn batch_transfer_spl_tokens<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchTransfer<'info>>,
+    amounts: Vec<u64>
+) -> Result<()> {
+    let mint_key = ctx.accounts.mint.key();
+    let (destinations, hook_accounts) = ctx.remaining_accounts.split_at(
+        amounts.len().min(ctx.remaining_accounts.len())
+    );
+    let total_amount = batch_total(&amounts, destinations, mint_key)?;
+    require!(total_amount <= ctx.accounts.source.amount, BatchError::InsufficientBalance);
+
+    for (destination, amount) in destinations.iter().zip(amounts.iter()) {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.source.to_account_info(),
+            to: destination.clone(),
+            authority: ctx.accounts.owner.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts
+        ).with_remaining_accounts(hook_accounts.to_vec());
+        transfer_checked_with_hook(cpi_ctx, *amount, ctx.accounts.mint.decimals)?;
+    }
+
+    emit!(BatchTransferred {
+        mint: mint_key,
+        source: ctx.accounts.source.key(),
+        recipients: amounts.len() as u32,
+        total_amount,
+        time: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Validate every destination against `mint` and sum the batch
+fn batch_total<'info>(
+    amounts: &[u64],
+    destinations: &'info [AccountInfo<'info>],
+    mint: Pubkey
+) -> Result<u64> {
+    require!(!amounts.is_empty(), BatchError::EmptyBatch);
+    require!(amounts.len() == destinations.len(), BatchError::LengthMismatch);
+
+    let mut total_amount: u64 = 0;
+    for (destination, amount) in destinations.iter().zip(amounts.iter()) {
+        require!(destination.is_writable, BatchError::DestinationNotWritable);
+        let token_account = InterfaceAccount::<TokenAccount>::try_from(destination)?;
+        require_keys_eq!(token_account.mint, mint, BatchError::InvalidDestinationMint);
+        total_amount = total_amount.checked_add(*amount).ok_or(BatchError::MathOverflow)?;
+    }
+    Ok(total_amount)
+}
+
+/// BATCH ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct BatchMintTokens<'info> {
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// Supply cap for the mint
+    #[account(seeds = [MINT_CONFIG_SEED, mint.key().as_ref()], bump = mint_config.bump)]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+    /// A registered minter for this mint
+    pub authority: Signer<'info>,
+    /// Registry entry carrying the minter's allowance and rate limit
+    #[account(
+        mut,
+        seeds = [MINTER_SEED, mint.key().as_ref(), authority.key().as_ref()],
+        bump = minter.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub minter: Box<Account<'info, Minter>>,
+    /// CHECK: program PDA holding the mint authority
+    #[account(seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BatchTransfer<'info> {
+    pub owner: Signer<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub source: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct BatchMinted {
+    pub mint: Pubkey,
+    pub minter: Pubkey,
+    pub recipients: u32,
+    pub total_amount: u64,
+    pub time: i64,
+}
+
+#[event]
+pub struct BatchTransferred {
+    pub mint: Pubkey,
+    pub source: Pubkey,
+    pub recipients: u32,
+    pub total_amount: u64,
+    pub time: i64,
+}
+
+#[error_code]
+pub enum BatchError {
+    #[msg("batch must contain at least one destination")]
+    EmptyBatch,
+    #[msg("amounts and destination accounts must have the same length")]
+    LengthMismatch,
+    #[msg("destination account must be writable")]
+    DestinationNotWritable,
+    #[msg("destination token account belongs to a different mint")]
+    InvalidDestinationMint,
+    #[msg("source account balance is too low for the batch")]
+    InsufficientBalance,
+    #[msg("math overflow")]
+    MathOverflow,
+}
@@ -0,0 +1,324 @@
+use anchor_lang::{ prelude::*, solana_program::program::invoke };
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        extension::{
+            confidential_transfer::instruction::{
+                approve_account,
+                deposit,
+                inner_apply_pending_balance,
+                inner_configure_account,
+                inner_withdraw,
+                PubkeyValidityData,
+                WithdrawData,
+            },
+            ExtensionType,
+        },
+        proof::ProofLocation,
+        solana_zk_token_sdk::zk_token_elgamal::pod::AeCiphertext,
+    },
+    token_interface::{ Mint, Token2022, TokenAccount },
+};
+use bytemuck::Zeroable;
+use std::num::NonZeroI8;
+
+use crate::instructions::reallocate_for_extensions;
+
+/// Options for the ConfidentialTransferMint extension
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ConfidentialTransferArgs {
+    /// New accounts may transact confidentially without approval from the mint authority
+    pub auto_approve_new_accounts: bool,
+    /// ElGamal public key that can decrypt every confidential transfer amount
+    pub auditor_elgamal_pubkey: Option<[u8; 32]>,
+}
+
+/// CONFIDENTIAL TRANSFER FUNCTIONS
+// Add the ConfidentialTransferAccount extension to a token account and register its ElGamal key
+pub fn configure_confidential_token_account(
+    ctx: Context<ConfigureConfidentialAccount>,
+    decryptable_zero_balance: [u8; 36],
+    maximum_pending_balance_credit_counter: u64,
+    proof_instruction_offset: i8
+) -> Result<()> {
+    let token_program_id = ctx.accounts.token_program.key();
+    let token_account_key = ctx.accounts.token_account.key();
+    let owner_key = ctx.accounts.owner.key();
+
+    // Grow the account so it can hold the confidential balances
+    reallocate_for_extensions(
+        &ctx.accounts.token_program,
+        &ctx.accounts.token_account.to_account_info(),
+        &ctx.accounts.owner,
+        &ctx.accounts.system_program,
+        &[ExtensionType::ConfidentialTransferAccount]
+    )?;
+
+    let placeholder = PubkeyValidityData::zeroed();
+    let ix = inner_configure_account(
+        &token_program_id,
+        &token_account_key,
+        &ctx.accounts.mint.key(),
+        decryptable_zero_balance_ciphertext(decryptable_zero_balance)?,
+        maximum_pending_balance_credit_counter,
+        &owner_key,
+        &[],
+        proof_location(proof_instruction_offset, ctx.accounts.proof_account.key, &placeholder)
+    )?;
+    invoke(
+        &ix,
+        &[
+            ctx.accounts.token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.proof_account.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+        ]
+    )?;
+
+    emit!(ConfidentialAccountConfigured {
+        mint: ctx.accounts.mint.key(),
+        token_account: token_account_key,
+        owner: owner_key,
+    });
+    Ok(())
+}
+
+// Approve a configured account on mints that do not auto-approve new accounts
+pub fn approve_confidential_token_account(ctx: Context<ApproveConfidentialAccount>) -> Result<()> {
+    let ix = approve_account(
+        ctx.accounts.token_program.key,
+        &ctx.accounts.token_account.key(),
+        &ctx.accounts.mint.key(),
+        ctx.accounts.authority.key,
+        &[]
+    )?;
+    invoke(
+        &ix,
+        &[
+            ctx.accounts.token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+        ]
+    )?;
+
+    emit!(ConfidentialAccountApproved {
+        mint: ctx.accounts.mint.key(),
+        token_account: ctx.accounts.token_account.key(),
+    });
+    Ok(())
+}
+
+// Move public tokens into the account's pending confidential balance
+pub fn deposit_confidential_tokens(ctx: Context<DepositConfidential>, amount: u64) -> Result<()> {
+    require!(amount > 0, ConfidentialTransferError::ZeroAmount);
+
+    let ix = deposit(
+        ctx.accounts.token_program.key,
+        &ctx.accounts.token_account.key(),
+        &ctx.accounts.mint.key(),
+        amount,
+        ctx.accounts.mint.decimals,
+        ctx.accounts.owner.key,
+        &[]
+    )?;
+    invoke(
+        &ix,
+        &[
+            ctx.accounts.token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+        ]
+    )?;
+
+    emit!(ConfidentialDeposit {
+        mint: ctx.accounts.mint.key(),
+        token_account: ctx.accounts.token_account.key(),
+        amount,
+    });
+    Ok(())
+}
+
+// Fold the pending balance into the available balance
+pub fn apply_pending_confidential_balance(
+    ctx: Context<ApplyPendingBalance>,
+    expected_pending_balance_credit_counter: u64,
+    new_decryptable_available_balance: [u8; 36]
+) -> Result<()> {
+    let ix = inner_apply_pending_balance(
+        ctx.accounts.token_program.key,
+        &ctx.accounts.token_account.key(),
+        expected_pending_balance_credit_counter,
+        AeCiphertext(new_decryptable_available_balance),
+        ctx.accounts.owner.key,
+        &[]
+    )?;
+    invoke(
+        &ix,
+        &[ctx.accounts.token_account.to_account_info(), ctx.accounts.owner.to_account_info()]
+    )?;
+
+    emit!(PendingBalanceApplied {
+        token_account: ctx.accounts.token_account.key(),
+        credit_counter: expected_pending_balance_credit_counter,
+    });
+    Ok(())
+}
+
+// Move tokens from the available confidential balance back to the public balance
+pub fn withdraw_confidential_tokens(
+    ctx: Context<WithdrawConfidential>,
+    amount: u64,
+    new_decryptable_available_balance: [u8; 36],
+    proof_instruction_offset: i8
+) -> Result<()> {
+    require!(amount > 0, ConfidentialTransferError::ZeroAmount);
+
+    let placeholder = WithdrawData::zeroed();
+    let ix = inner_withdraw(
+        ctx.accounts.token_program.key,
+        &ctx.accounts.token_account.key(),
+        &ctx.accounts.mint.key(),
+        amount,
+        ctx.accounts.mint.decimals,
+        AeCiphertext(new_decryptable_available_balance),
+        ctx.accounts.owner.key,
+        &[],
+        proof_location(proof_instruction_offset, ctx.accounts.proof_account.key, &placeholder)
+    )?;
+    invoke(
+        &ix,
+        &[
+            ctx.accounts.token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.proof_account.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+        ]
+    )?;
+
+    emit!(ConfidentialWithdraw {
+        mint: ctx.accounts.mint.key(),
+        token_account: ctx.accounts.token_account.key(),
+        amount,
+    });
+    Ok(())
+}
+
+// Where Token-2022 finds a pre-generated proof: a sibling zk proof instruction at
+// `offset` from this instruction, or (offset 0) a pre-verified context state account.
+// The builders only read the offset, so the proof data itself is a placeholder.
+fn proof_location<'a, T>(offset: i8, proof_account: &'a Pubkey, placeholder: &'a T) -> ProofLocation<'a, T> {
+    match NonZeroI8::new(offset) {
+        Some(offset) => ProofLocation::InstructionOffset(offset, placeholder),
+        None => ProofLocation::ContextStateAccount(proof_account),
+    }
+}
+
+// `inner_configure_account` takes the pod ciphertext on-chain but the decoded one off-chain
+#[cfg(target_os = "solana")]
+fn decryptable_zero_balance_ciphertext(bytes: [u8; 36]) -> Result<AeCiphertext> {
+    Ok(AeCiphertext(bytes))
+}
+
+#[cfg(not(target_os = "solana"))]
+fn decryptable_zero_balance_ciphertext(
+    bytes: [u8; 36]
+) -> Result<anchor_spl::token_2022::spl_token_2022::solana_zk_token_sdk::encryption::auth_encryption::AeCiphertext> {
+    AeCiphertext(bytes)
+        .try_into()
+        .map_err(|_| error!(ConfidentialTransferError::InvalidCiphertext))
+}
+
+/// CONFIDENTIAL TRANSFER ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct ConfigureConfidentialAccount<'info> {
+    /// Owner of the token account, also pays for the reallocation
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: instructions sysvar when the proof is in a sibling instruction, otherwise a context state account; checked by Token-2022
+    pub proof_account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveConfidentialAccount<'info> {
+    /// The mint's confidential transfer authority
+    pub authority: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, token::mint = mint)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct DepositConfidential<'info> {
+    pub owner: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyPendingBalance<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut, token::authority = owner)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawConfidential<'info> {
+    pub owner: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: instructions sysvar when the proof is in a sibling instruction, otherwise a context state account; checked by Token-2022
+    pub proof_account: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event]
+pub struct ConfidentialAccountConfigured {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct ConfidentialAccountApproved {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+}
+
+#[event]
+pub struct ConfidentialDeposit {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PendingBalanceApplied {
+    pub token_account: Pubkey,
+    pub credit_counter: u64,
+}
+
+#[event]
+pub struct ConfidentialWithdraw {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum ConfidentialTransferError {
+    #[msg("amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("decryptable balance is not a valid ciphertext")]
+    InvalidCiphertext,
+    #[msg("confidential transfers cannot be combined with transfer fees")]
+    TransferFeeNotSupported,
+}
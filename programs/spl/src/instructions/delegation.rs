@@ -0,0 +1,282 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::{ approve, burn, revoke, Approve, Burn, Revoke, TransferChecked },
+    token_interface::{ Mint, TokenAccount, TokenInterface },
+};
+use std::mem::size_of;
+
+use crate::{ state::*, utils::* };
+
+/// DELEGATION FUNCTIONS
+// Let `delegate` spend up to `amount` from a token account until `expires_at` (0 = never expires).
+// Token-2022 sees the allowance PDA as the delegate, so every spend goes through this program.
+pub fn approve_token_delegate(
+    ctx: Context<ApproveDelegate>,
+    amount: u64,
+    expires_at: i64
+) -> Result<()> {
+    require!(amount > 0, DelegationError::ZeroAmount);
+    let now = Clock::get()?.unix_timestamp;
+    require!(expires_at == 0 || expires_at > now, DelegationError::InvalidExpiry);
+
+    let cpi_accounts = Approve {
+        to: ctx.accounts.token_account.to_account_info(),
+        delegate: ctx.accounts.allowance.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    approve(cpi_ctx, amount)?;
+
+    // Re-approving replaces the previous delegate and allowance
+    let allowance = &mut ctx.accounts.allowance;
+    allowance.token_account = ctx.accounts.token_account.key();
+    allowance.mint = ctx.accounts.token_account.mint;
+    allowance.owner = ctx.accounts.owner.key();
+    allowance.delegate = ctx.accounts.delegate.key();
+    allowance.remaining_amount = amount;
+    allowance.expires_at = expires_at;
+    allowance.bump = ctx.bumps.allowance;
+
+    emit!(DelegateApproved {
+        token_account: allowance.token_account,
+        owner: allowance.owner,
+        delegate: allowance.delegate,
+        amount,
+        expires_at,
+    });
+    Ok(())
+}
+
+// Remove the delegate from the token account and reclaim the allowance's rent
+pub fn revoke_token_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+    let cpi_accounts = Revoke {
+        source: ctx.accounts.token_account.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    revoke(cpi_ctx)?;
+
+    emit!(DelegateRevoked {
+        token_account: ctx.accounts.token_account.key(),
+        owner: ctx.accounts.owner.key(),
+        delegate: ctx.accounts.allowance.delegate,
+        unused_amount: ctx.accounts.allowance.remaining_amount,
+    });
+    Ok(())
+}
+
+// Transfer out of the owner's account as the delegate, within the allowance
+pub fn delegated_transfer_tokens<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DelegatedTransfer<'info>>,
+    amount: u64
+) -> Result<()> {
+    let remaining_amount = spend_allowance(&mut ctx.accounts.allowance, amount)?;
+
+    let token_account_key = ctx.accounts.source.key();
+    let seeds: &[&[u8]] = &[
+        DELEGATE_ALLOWANCE_SEED,
+        token_account_key.as_ref(),
+        &[ctx.accounts.allowance.bump],
+    ];
+    let signer_seeds = &[seeds];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.source.to_account_info(),
+        to: ctx.accounts.destination.to_account_info(),
+        authority: ctx.accounts.allowance.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds
+    ).with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    emit!(DelegateTransferExecuted {
+        source: token_account_key,
+        destination: ctx.accounts.destination.key(),
+        delegate: ctx.accounts.delegate.key(),
+        amount,
+        remaining_amount,
+        time: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Burn from the owner's account as the delegate, within the allowance
+pub fn delegated_burn_tokens(ctx: Context<DelegatedBurn>, amount: u64) -> Result<()> {
+    let remaining_amount = spend_allowance(&mut ctx.accounts.allowance, amount)?;
+
+    let token_account_key = ctx.accounts.source.key();
+    let seeds: &[&[u8]] = &[
+        DELEGATE_ALLOWANCE_SEED,
+        token_account_key.as_ref(),
+        &[ctx.accounts.allowance.bump],
+    ];
+    let signer_seeds = &[seeds];
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.mint.to_account_info(),
+        from: ctx.accounts.source.to_account_info(),
+        authority: ctx.accounts.allowance.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds
+    );
+    burn(cpi_ctx, amount)?;
+
+    emit!(DelegateBurnExecuted {
+        source: token_account_key,
+        delegate: ctx.accounts.delegate.key(),
+        amount,
+        remaining_amount,
+        time: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Check expiry and deduct `amount` from the allowance, returning what is left
+fn spend_allowance(allowance: &mut DelegateAllowance, amount: u64) -> Result<u64> {
+    require!(amount > 0, DelegationError::ZeroAmount);
+    require!(
+        allowance.expires_at == 0 || Clock::get()?.unix_timestamp < allowance.expires_at,
+        DelegationError::AllowanceExpired
+    );
+    allowance.remaining_amount = allowance.remaining_amount
+        .checked_sub(amount)
+        .ok_or(DelegationError::AllowanceExceeded)?;
+    Ok(allowance.remaining_amount)
+}
+
+/// DELEGATION ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct ApproveDelegate<'info> {
+    /// Owner of the token account (pays for the allowance)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut, token::authority = owner, token::token_program = token_program)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: wallet allowed to spend through the allowance
+    pub delegate: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [DELEGATE_ALLOWANCE_SEED, token_account.key().as_ref()],
+        bump,
+        space = 8 + size_of::<DelegateAllowance>()
+    )]
+    pub allowance: Box<Account<'info, DelegateAllowance>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegate<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut, token::authority = owner, token::token_program = token_program)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        close = owner,
+        seeds = [DELEGATE_ALLOWANCE_SEED, token_account.key().as_ref()],
+        bump = allowance.bump,
+        has_one = owner,
+        has_one = token_account
+    )]
+    pub allowance: Box<Account<'info, DelegateAllowance>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct DelegatedTransfer<'info> {
+    /// Wallet named in the allowance
+    pub delegate: Signer<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// Owner's account the tokens are pulled from
+    #[account(mut, token::mint = mint)]
+    pub source: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = mint)]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [DELEGATE_ALLOWANCE_SEED, source.key().as_ref()],
+        bump = allowance.bump,
+        has_one = delegate @ DelegationError::NotDelegate
+    )]
+    pub allowance: Box<Account<'info, DelegateAllowance>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct DelegatedBurn<'info> {
+    /// Wallet named in the allowance
+    pub delegate: Signer<'info>,
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// Owner's account the tokens are burned from
+    #[account(mut, token::mint = mint)]
+    pub source: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [DELEGATE_ALLOWANCE_SEED, source.key().as_ref()],
+        bump = allowance.bump,
+        has_one = delegate @ DelegationError::NotDelegate
+    )]
+    pub allowance: Box<Account<'info, DelegateAllowance>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct DelegateApproved {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub amount: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct DelegateRevoked {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub unused_amount: u64,
+}
+
+#[event]
+pub struct DelegateTransferExecuted {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub delegate: Pubkey,
+    pub amount: u64,
+    pub remaining_amount: u64,
+    pub time: i64,
+}
+
+#[event]
+pub struct DelegateBurnExecuted {
+    pub source: Pubkey,
+    pub delegate: Pubkey,
+    pub amount: u64,
+    pub remaining_amount: u64,
+    pub time: i64,
+}
+
+#[error_code]
+pub enum DelegationError {
+    #[msg("amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("expiry must be zero or in the future")]
+    InvalidExpiry,
+    #[msg("delegate allowance has expired")]
+    AllowanceExpired,
+    #[msg("amount exceeds the remaining delegate allowance")]
+    AllowanceExceeded,
+    #[msg("signer is not the approved delegate")]
+    NotDelegate,
+}
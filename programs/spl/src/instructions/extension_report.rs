@@ -0,0 +1,282 @@
+use anchor_lang::{ prelude::*, solana_program::program::MAX_RETURN_DATA };
+use anchor_spl::{
+    token_2022_extensions::spl_token_metadata_interface::state::TokenMetadata,
+    token_interface::{
+        spl_token_2022::{
+            extension::{
+                confidential_transfer::ConfidentialTransferMint,
+                default_account_state::DefaultAccountState,
+                group_member_pointer::GroupMemberPointer,
+                group_pointer::GroupPointer,
+                interest_bearing_mint::InterestBearingConfig,
+                metadata_pointer::MetadataPointer,
+                mint_close_authority::MintCloseAuthority,
+                permanent_delegate::PermanentDelegate,
+                transfer_fee::{ TransferFee, TransferFeeConfig },
+                transfer_hook::TransferHook,
+                ExtensionType,
+            },
+            solana_zk_token_sdk::zk_token_elgamal::pod::ElGamalPubkey,
+            state::AccountState,
+        },
+        Mint,
+    },
+};
+use spl_token_group_interface::state::{ TokenGroup, TokenGroupMember };
+
+use crate::utils::*;
+
+/// Everything a mint's extensions expose, in the order they appear on the account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MintExtensionReport {
+    pub mint: Pubkey,
+    /// Owning token program (classic SPL Token mints report no extensions)
+    pub token_program: Pubkey,
+    pub extensions: Vec<ExtensionReport>,
+}
+
+/// One extension found on the mint, with its configuration
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum ExtensionReport {
+    TransferFeeConfig {
+        transfer_fee_config_authority: Option<Pubkey>,
+        withdraw_withheld_authority: Option<Pubkey>,
+        withheld_amount: u64,
+        older_transfer_fee: TransferFeeReport,
+        newer_transfer_fee: TransferFeeReport,
+    },
+    MintCloseAuthority {
+        close_authority: Option<Pubkey>,
+    },
+    ConfidentialTransferMint {
+        authority: Option<Pubkey>,
+        auto_approve_new_accounts: bool,
+        auditor_elgamal_pubkey: Option<[u8; 32]>,
+    },
+    DefaultAccountState {
+        frozen: bool,
+    },
+    NonTransferable,
+    InterestBearingConfig {
+        rate_authority: Option<Pubkey>,
+        current_rate: i16,
+        last_update_timestamp: i64,
+    },
+    PermanentDelegate {
+        delegate: Option<Pubkey>,
+    },
+    TransferHook {
+        authority: Option<Pubkey>,
+        program_id: Option<Pubkey>,
+    },
+    MetadataPointer {
+        authority: Option<Pubkey>,
+        metadata_address: Option<Pubkey>,
+    },
+    TokenMetadata {
+        update_authority: Option<Pubkey>,
+        name: String,
+        symbol: String,
+        uri: String,
+        additional_metadata: Vec<MetadataEntry>,
+    },
+    GroupPointer {
+        authority: Option<Pubkey>,
+        group_address: Option<Pubkey>,
+    },
+    TokenGroup {
+        update_authority: Option<Pubkey>,
+        size: u32,
+        max_size: u32,
+    },
+    GroupMemberPointer {
+        authority: Option<Pubkey>,
+        member_address: Option<Pubkey>,
+    },
+    TokenGroupMember {
+        group: Pubkey,
+        member_number: u32,
+    },
+    /// Extension this program does not decode, by its Token-2022 type id
+    Other {
+        extension_type: u16,
+    },
+}
+
+/// Fee schedule entry of a TransferFeeConfig
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TransferFeeReport {
+    /// First epoch the fee applies to
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub transfer_fee_basis_points: u16,
+}
+
+impl From<TransferFee> for TransferFeeReport {
+    fn from(fee: TransferFee) -> Self {
+        Self {
+            epoch: fee.epoch.into(),
+            maximum_fee: fee.maximum_fee.into(),
+            transfer_fee_basis_points: fee.transfer_fee_basis_points.into(),
+        }
+    }
+}
+
+/// Custom key/value pair from the token metadata
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MetadataEntry {
+    pub key: String,
+    pub value: String,
+}
+
+/// EXTENSION REPORT FUNCTIONS
+// Decode every extension on the mint into a report returned through return data
+pub fn inspect_mint_extensions(
+    ctx: Context<CheckMintExtensionConstraints>
+) -> Result<MintExtensionReport> {
+    let mut mint_info = ctx.accounts.mint.to_account_info();
+
+    let mut extensions = Vec::new();
+    for extension_type in get_mint_extension_types(&mint_info)? {
+        extensions.push(extension_report(&mut mint_info, extension_type)?);
+    }
+
+    let report = MintExtensionReport {
+        mint: mint_info.key(),
+        token_program: *mint_info.owner,
+        extensions,
+    };
+    // Return data is capped by the runtime; fail clearly instead of aborting on long metadata
+    require!(
+        report.try_to_vec()?.len() <= MAX_RETURN_DATA,
+        ExtensionReportError::ReportTooLarge
+    );
+    Ok(report)
+}
+
+// Decode a single extension of `mint`
+fn extension_report(
+    mint: &mut AccountInfo,
+    extension_type: ExtensionType
+) -> Result<ExtensionReport> {
+    let report = match extension_type {
+        ExtensionType::TransferFeeConfig => {
+            let config = get_mint_extension_data::<TransferFeeConfig>(mint)?;
+            ExtensionReport::TransferFeeConfig {
+                transfer_fee_config_authority: config.transfer_fee_config_authority.into(),
+                withdraw_withheld_authority: config.withdraw_withheld_authority.into(),
+                withheld_amount: config.withheld_amount.into(),
+                older_transfer_fee: config.older_transfer_fee.into(),
+                newer_transfer_fee: config.newer_transfer_fee.into(),
+            }
+        }
+        ExtensionType::MintCloseAuthority => {
+            let config = get_mint_extension_data::<MintCloseAuthority>(mint)?;
+            ExtensionReport::MintCloseAuthority {
+                close_authority: config.close_authority.into(),
+            }
+        }
+        ExtensionType::ConfidentialTransferMint => {
+            let config = get_mint_extension_data::<ConfidentialTransferMint>(mint)?;
+            ExtensionReport::ConfidentialTransferMint {
+                authority: config.authority.into(),
+                auto_approve_new_accounts: config.auto_approve_new_accounts.into(),
+                auditor_elgamal_pubkey: Option::<ElGamalPubkey>::from(
+                    config.auditor_elgamal_pubkey
+                ).map(|pubkey| pubkey.0),
+            }
+        }
+        ExtensionType::DefaultAccountState => {
+            let config = get_mint_extension_data::<DefaultAccountState>(mint)?;
+            ExtensionReport::DefaultAccountState {
+                frozen: config.state == (AccountState::Frozen as u8),
+            }
+        }
+        ExtensionType::NonTransferable => ExtensionReport::NonTransferable,
+        ExtensionType::InterestBearingConfig => {
+            let config = get_mint_extension_data::<InterestBearingConfig>(mint)?;
+            ExtensionReport::InterestBearingConfig {
+                rate_authority: config.rate_authority.into(),
+                current_rate: config.current_rate.into(),
+                last_update_timestamp: config.last_update_timestamp.into(),
+            }
+        }
+        ExtensionType::PermanentDelegate => {
+            let config = get_mint_extension_data::<PermanentDelegate>(mint)?;
+            ExtensionReport::PermanentDelegate {
+                delegate: config.delegate.into(),
+            }
+        }
+        ExtensionType::TransferHook => {
+            let config = get_mint_extension_data::<TransferHook>(mint)?;
+            ExtensionReport::TransferHook {
+                authority: config.authority.into(),
+                program_id: config.program_id.into(),
+            }
+        }
+        ExtensionType::MetadataPointer => {
+            let config = get_mint_extension_data::<MetadataPointer>(mint)?;
+            ExtensionReport::MetadataPointer {
+                authority: config.authority.into(),
+                metadata_address: config.metadata_address.into(),
+            }
+        }
+        ExtensionType::TokenMetadata => {
+            let metadata = get_mint_extensible_extension_data::<TokenMetadata>(mint)?;
+            ExtensionReport::TokenMetadata {
+                update_authority: metadata.update_authority.into(),
+                name: metadata.name,
+                symbol: metadata.symbol,
+                uri: metadata.uri,
+                additional_metadata: metadata.additional_metadata
+                    .into_iter()
+                    .map(|(key, value)| MetadataEntry { key, value })
+                    .collect(),
+            }
+        }
+        ExtensionType::GroupPointer => {
+            let config = get_mint_extension_data::<GroupPointer>(mint)?;
+            ExtensionReport::GroupPointer {
+                authority: config.authority.into(),
+                group_address: config.group_address.into(),
+            }
+        }
+        ExtensionType::TokenGroup => {
+            let group = get_mint_extension_data::<TokenGroup>(mint)?;
+            ExtensionReport::TokenGroup {
+                update_authority: group.update_authority.into(),
+                size: group.size.into(),
+                max_size: group.max_size.into(),
+            }
+        }
+        ExtensionType::GroupMemberPointer => {
+            let config = get_mint_extension_data::<GroupMemberPointer>(mint)?;
+            ExtensionReport::GroupMemberPointer {
+                authority: config.authority.into(),
+                member_address: config.member_address.into(),
+            }
+        }
+        ExtensionType::TokenGroupMember => {
+            let member = get_mint_extension_data::<TokenGroupMember>(mint)?;
+            ExtensionReport::TokenGroupMember {
+                group: member.group,
+                member_number: member.member_number.into(),
+            }
+        }
+        other => ExtensionReport::Other { extension_type: other as u16 },
+    };
+    Ok(report)
+}
+
+/// EXTENSION REPORT ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct CheckMintExtensionConstraints<'info> {
+    /// Any classic SPL Token or Token-2022 mint
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+#[error_code]
+pub enum ExtensionReportError {
+    #[msg("extension report exceeds the return data limit")]
+    ReportTooLarge,
+}
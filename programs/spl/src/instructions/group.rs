@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022_extensions::{
+        token_group_initialize,
+        token_member_initialize,
+        TokenGroupInitialize,
+        TokenMemberInitialize,
+    },
+    token_interface::{ Mint, Token2022 },
+};
+use spl_token_group_interface::state::{ TokenGroup, TokenGroupMember };
+
+use crate::{ state::*, utils::* };
+
+/// TOKEN GROUP FUNCTIONS
+// Turn a group-pointer mint into a collection that accepts up to `max_size` members
+pub fn initialize_token_group(ctx: Context<CreateGroup>, max_size: u32) -> Result<()> {
+    require!(max_size > 0, GroupError::InvalidMaxSize);
+
+    // The program PDA holds the mint authority, so it signs for the group
+    let mint_key = ctx.accounts.mint.key();
+    let seeds: &[&[u8]] = &[MINT_AUTHORITY_SEED, mint_key.as_ref(), &[ctx.bumps.mint_authority]];
+    let signer_seeds = &[seeds];
+
+    let cpi_accounts = TokenGroupInitialize {
+        token_program_id: ctx.accounts.token_program.to_account_info(),
+        group: ctx.accounts.mint.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        mint_authority: ctx.accounts.mint_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds
+    );
+    token_group_initialize(cpi_ctx, Some(ctx.accounts.authority.key()), max_size)?;
+
+    // Keep the mint rent-exempt after the group data was appended
+    update_account_lamports_to_minimum_balance(
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    emit!(GroupCreated {
+        group: mint_key,
+        update_authority: ctx.accounts.authority.key(),
+        max_size,
+    });
+    Ok(())
+}
+
+// Register a member-pointer mint in a group, bumping the group's size
+pub fn add_token_group_member(ctx: Context<AddGroupMember>) -> Result<()> {
+    let member_key = ctx.accounts.member_mint.key();
+    let seeds: &[&[u8]] = &[
+        MINT_AUTHORITY_SEED,
+        member_key.as_ref(),
+        &[ctx.bumps.member_mint_authority],
+    ];
+    let signer_seeds = &[seeds];
+
+    let cpi_accounts = TokenMemberInitialize {
+        token_program_id: ctx.accounts.token_program.to_account_info(),
+        member: ctx.accounts.member_mint.to_account_info(),
+        member_mint: ctx.accounts.member_mint.to_account_info(),
+        member_mint_authority: ctx.accounts.member_mint_authority.to_account_info(),
+        group: ctx.accounts.group_mint.to_account_info(),
+        group_update_authority: ctx.accounts.group_update_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds
+    );
+    token_member_initialize(cpi_ctx)?;
+
+    update_account_lamports_to_minimum_balance(
+        ctx.accounts.member_mint.to_account_info(),
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    let member = get_mint_extension_data::<TokenGroupMember>(
+        &mut ctx.accounts.member_mint.to_account_info()
+    )?;
+    let group = get_mint_extension_data::<TokenGroup>(
+        &mut ctx.accounts.group_mint.to_account_info()
+    )?;
+
+    emit!(GroupMemberAdded {
+        group: ctx.accounts.group_mint.key(),
+        member: member_key,
+        member_number: member.member_number.into(),
+        size: group.size.into(),
+    });
+    Ok(())
+}
+
+/// TOKEN GROUP ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct CreateGroup<'info> {
+    /// Admin of the mint; becomes the group's update authority and pays the extra rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Mint created with the group pointer pointing at itself
+    #[account(mut, extensions::group_pointer::group_address = mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [MINT_CONFIG_SEED, mint.key().as_ref()],
+        bump = mint_config.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+    /// CHECK: program PDA holding the mint authority
+    #[account(seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct AddGroupMember<'info> {
+    /// Admin of the member mint (also pays the extra rent)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Mint created with the group member pointer pointing at itself
+    #[account(
+        mut,
+        constraint = member_mint.key() != group_mint.key() @ GroupError::SelfMembership,
+        extensions::group_member_pointer::member_address = member_mint
+    )]
+    pub member_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [MINT_CONFIG_SEED, member_mint.key().as_ref()],
+        bump = member_mint_config.bump,
+        constraint = member_mint_config.authority == authority.key() @ GroupError::Unauthorized
+    )]
+    pub member_mint_config: Box<Account<'info, MintConfig>>,
+    /// CHECK: program PDA holding the member mint's authority
+    #[account(seeds = [MINT_AUTHORITY_SEED, member_mint.key().as_ref()], bump)]
+    pub member_mint_authority: UncheckedAccount<'info>,
+    /// The group mint; its size is incremented by Token-2022
+    #[account(mut, extensions::group_pointer::group_address = group_mint)]
+    pub group_mint: Box<InterfaceAccount<'info, Mint>>,
+    /// Must match the group's update authority
+    pub group_update_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event]
+pub struct GroupCreated {
+    pub group: Pubkey,
+    pub update_authority: Pubkey,
+    pub max_size: u32,
+}
+
+#[event]
+pub struct GroupMemberAdded {
+    pub group: Pubkey,
+    pub member: Pubkey,
+    pub member_number: u32,
+    pub size: u32,
+}
+
+#[error_code]
+pub enum GroupError {
+    #[msg("group max size must be greater than zero")]
+    InvalidMaxSize,
+    #[msg("a mint cannot be a member of its own group")]
+    SelfMembership,
+    #[msg("signer is not the member mint's admin")]
+    Unauthorized,
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::{ amount_to_ui_amount, AmountToUiAmount },
+    token_2022_extensions::{ interest_bearing_mint_update_rate, InterestBearingMintUpdateRate },
+    token_interface::{ Mint, Token2022 },
+};
+
+/// INTEREST BEARING FUNCTIONS
+// Update the interest rate of an interest-bearing mint (rate authority only)
+pub fn set_interest_rate(ctx: Context<UpdateInterestRate>, rate: i16) -> Result<()> {
+    let cpi_accounts = InterestBearingMintUpdateRate {
+        token_program_id: ctx.accounts.token_program.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        rate_authority: ctx.accounts.rate_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    interest_bearing_mint_update_rate(cpi_ctx, rate)?;
+
+    emit!(InterestRateUpdated {
+        mint: ctx.accounts.mint.key(),
+        rate_authority: ctx.accounts.rate_authority.key(),
+        rate,
+        time: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Convert a raw amount to its UI amount, including accrued interest
+pub fn calculate_ui_amount(ctx: Context<UiAmount>, amount: u64) -> Result<String> {
+    let cpi_accounts = AmountToUiAmount {
+        account: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    amount_to_ui_amount(cpi_ctx, amount)
+}
+
+/// INTEREST BEARING ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct UpdateInterestRate<'info> {
+    /// Must match the mint's interest rate authority
+    pub rate_authority: Signer<'info>,
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct UiAmount<'info> {
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event]
+pub struct InterestRateUpdated {
+    pub mint: Pubkey,
+    pub rate_authority: Pubkey,
+    pub rate: i16,
+    pub time: i64,
+}
@@ -0,0 +1,284 @@
+use anchor_lang::{ prelude::*, solana_program::program_option::COption };
+use anchor_spl::{
+    token_2022::{
+        freeze_account,
+        set_authority,
+        spl_token_2022::instruction::AuthorityType,
+        thaw_account,
+        FreezeAccount,
+        SetAuthority,
+        ThawAccount,
+    },
+    token_interface::{ Mint, Token2022, TokenAccount },
+};
+use std::mem::size_of;
+
+use crate::state::*;
+
+/// KYC FUNCTIONS
+// Gate a mint behind KYC by handing its freeze authority to a program PDA (freeze authority
+// only), or replace the approver of an already gated mint (the wallet that gated it only)
+pub fn set_kyc_approver(ctx: Context<SetKycApprover>, approver: Pubkey) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    if ctx.accounts.kyc_config.mint == Pubkey::default() {
+        require!(
+            ctx.accounts.mint.freeze_authority == COption::Some(authority),
+            KycError::NotFreezeAuthority
+        );
+        let cpi_accounts = SetAuthority {
+            current_authority: ctx.accounts.authority.to_account_info(),
+            account_or_mint: ctx.accounts.mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        set_authority(
+            cpi_ctx,
+            AuthorityType::FreezeAccount,
+            Some(ctx.accounts.kyc_freeze_authority.key())
+        )?;
+
+        let kyc_config = &mut ctx.accounts.kyc_config;
+        kyc_config.mint = ctx.accounts.mint.key();
+        kyc_config.authority = authority;
+        kyc_config.bump = ctx.bumps.kyc_config;
+        kyc_config.freeze_authority_bump = ctx.bumps.kyc_freeze_authority;
+    } else {
+        require_keys_eq!(ctx.accounts.kyc_config.authority, authority, KycError::NotKycAuthority);
+    }
+    let kyc_config = &mut ctx.accounts.kyc_config;
+    kyc_config.approver = approver;
+
+    emit!(KycApproverSet {
+        mint: kyc_config.mint,
+        approver,
+    });
+    Ok(())
+}
+
+// Approve (or re-approve) a wallet until `expires_at` (0 = never expires)
+pub fn approve_kyc(ctx: Context<ApproveKyc>, expires_at: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(expires_at == 0 || expires_at > now, KycError::InvalidExpiry);
+
+    let kyc_record = &mut ctx.accounts.kyc_record;
+    kyc_record.mint = ctx.accounts.mint.key();
+    kyc_record.wallet = ctx.accounts.wallet.key();
+    kyc_record.approver = ctx.accounts.approver.key();
+    kyc_record.approved_at = now;
+    kyc_record.expires_at = expires_at;
+    kyc_record.bump = ctx.bumps.kyc_record;
+
+    emit!(KycApproved {
+        mint: kyc_record.mint,
+        wallet: kyc_record.wallet,
+        approver: kyc_record.approver,
+        expires_at,
+    });
+    Ok(())
+}
+
+// Revoke a wallet's approval and reclaim the record's rent
+pub fn revoke_kyc(ctx: Context<RevokeKyc>) -> Result<()> {
+    emit!(KycRevoked {
+        mint: ctx.accounts.mint.key(),
+        wallet: ctx.accounts.wallet.key(),
+        approver: ctx.accounts.approver.key(),
+    });
+    Ok(())
+}
+
+// Thaw a token account whose owner holds a valid KYC approval (anyone can call)
+pub fn thaw_kyc_approved_account(ctx: Context<ThawKycTokenAccount>) -> Result<()> {
+    let expires_at = ctx.accounts.kyc_record.expires_at;
+    require!(
+        expires_at == 0 || Clock::get()?.unix_timestamp < expires_at,
+        KycError::KycExpired
+    );
+
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[
+        &[KYC_FREEZE_AUTHORITY_SEED, mint_key.as_ref(), &[ctx.accounts.kyc_config.freeze_authority_bump]],
+    ];
+    let cpi_accounts = ThawAccount {
+        account: ctx.accounts.account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.kyc_freeze_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds
+    );
+    thaw_account(cpi_ctx)?;
+    Ok(())
+}
+
+// Freeze a token account of a KYC-gated mint, e.g. after its owner's approval was revoked
+// (approver only, since the freeze authority is the program PDA)
+pub fn freeze_kyc_gated_account(ctx: Context<FreezeKycTokenAccount>) -> Result<()> {
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[
+        &[KYC_FREEZE_AUTHORITY_SEED, mint_key.as_ref(), &[ctx.accounts.kyc_config.freeze_authority_bump]],
+    ];
+    let cpi_accounts = FreezeAccount {
+        account: ctx.accounts.account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.kyc_freeze_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds
+    );
+    freeze_account(cpi_ctx)?;
+    Ok(())
+}
+
+/// KYC ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct SetKycApprover<'info> {
+    /// The mint's freeze authority on the first call, the wallet that gated the mint afterwards
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [KYC_CONFIG_SEED, mint.key().as_ref()],
+        bump,
+        space = 8 + size_of::<KycConfig>()
+    )]
+    pub kyc_config: Account<'info, KycConfig>,
+    /// CHECK: PDA that becomes the mint's freeze authority, never holds data
+    #[account(seeds = [KYC_FREEZE_AUTHORITY_SEED, mint.key().as_ref()], bump)]
+    pub kyc_freeze_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveKyc<'info> {
+    #[account(mut)]
+    pub approver: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [KYC_CONFIG_SEED, mint.key().as_ref()],
+        bump = kyc_config.bump,
+        has_one = approver,
+        has_one = mint
+    )]
+    pub kyc_config: Account<'info, KycConfig>,
+    /// CHECK: wallet being approved, only used as a seed
+    pub wallet: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = approver,
+        seeds = [KYC_RECORD_SEED, mint.key().as_ref(), wallet.key().as_ref()],
+        bump,
+        space = 8 + size_of::<KycRecord>()
+    )]
+    pub kyc_record: Account<'info, KycRecord>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeKyc<'info> {
+    #[account(mut)]
+    pub approver: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [KYC_CONFIG_SEED, mint.key().as_ref()],
+        bump = kyc_config.bump,
+        has_one = approver,
+        has_one = mint
+    )]
+    pub kyc_config: Account<'info, KycConfig>,
+    /// CHECK: wallet being revoked, only used as a seed
+    pub wallet: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = approver,
+        seeds = [KYC_RECORD_SEED, mint.key().as_ref(), wallet.key().as_ref()],
+        bump = kyc_record.bump
+    )]
+    pub kyc_record: Account<'info, KycRecord>,
+}
+
+#[derive(Accounts)]
+pub struct ThawKycTokenAccount<'info> {
+    /// The frozen token account of a KYC-approved wallet
+    #[account(mut, token::mint = mint)]
+    pub account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(seeds = [KYC_CONFIG_SEED, mint.key().as_ref()], bump = kyc_config.bump, has_one = mint)]
+    pub kyc_config: Account<'info, KycConfig>,
+    /// CHECK: PDA holding the mint's freeze authority, signs the thaw
+    #[account(
+        seeds = [KYC_FREEZE_AUTHORITY_SEED, mint.key().as_ref()],
+        bump = kyc_config.freeze_authority_bump
+    )]
+    pub kyc_freeze_authority: UncheckedAccount<'info>,
+    /// KYC approval of the token account's owner
+    #[account(
+        seeds = [KYC_RECORD_SEED, mint.key().as_ref(), account.owner.as_ref()],
+        bump = kyc_record.bump
+    )]
+    pub kyc_record: Account<'info, KycRecord>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeKycTokenAccount<'info> {
+    pub approver: Signer<'info>,
+    /// The token account to freeze
+    #[account(mut, token::mint = mint)]
+    pub account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [KYC_CONFIG_SEED, mint.key().as_ref()],
+        bump = kyc_config.bump,
+        has_one = approver,
+        has_one = mint
+    )]
+    pub kyc_config: Account<'info, KycConfig>,
+    /// CHECK: PDA holding the mint's freeze authority, signs the freeze
+    #[account(
+        seeds = [KYC_FREEZE_AUTHORITY_SEED, mint.key().as_ref()],
+        bump = kyc_config.freeze_authority_bump
+    )]
+    pub kyc_freeze_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event]
+pub struct KycApproverSet {
+    pub mint: Pubkey,
+    pub approver: Pubkey,
+}
+
+#[event]
+pub struct KycApproved {
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+    pub approver: Pubkey,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct KycRevoked {
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+    pub approver: Pubkey,
+}
+
+#[error_code]
+pub enum KycError {
+    #[msg("signer is not the mint's freeze authority")]
+    NotFreezeAuthority,
+    #[msg("expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("KYC approval has expired")]
+    KycExpired,
+    #[msg("signer is not the wallet that gated this mint")]
+    NotKycAuthority,
+}
@@ -0,0 +1,155 @@
+use anchor_lang::{ prelude::*, solana_program::program::invoke };
+use anchor_spl::{
+    token_2022_extensions::{
+        spl_pod::optional_keys::OptionalNonZeroPubkey,
+        spl_token_metadata_interface::{ instruction::remove_key, state::Field },
+        token_metadata_update_authority,
+        token_metadata_update_field,
+        TokenMetadataUpdateAuthority,
+        TokenMetadataUpdateField,
+    },
+    token_interface::{ Mint, Token2022 },
+};
+
+use crate::{ state::MetadataField, utils::* };
+
+impl From<MetadataField> for Field {
+    fn from(field: MetadataField) -> Self {
+        match field {
+            MetadataField::Name => Field::Name,
+            MetadataField::Symbol => Field::Symbol,
+            MetadataField::Uri => Field::Uri,
+            MetadataField::Key(key) => Field::Key(key),
+        }
+    }
+}
+
+/// TOKEN METADATA FUNCTIONS
+// Update a base field or custom key of the mint's metadata
+pub fn update_metadata_field(
+    ctx: Context<UpdateTokenMetadata>,
+    field: MetadataField,
+    value: String
+) -> Result<()> {
+    let field_name = match &field {
+        MetadataField::Name => "name".to_string(),
+        MetadataField::Symbol => "symbol".to_string(),
+        MetadataField::Uri => "uri".to_string(),
+        MetadataField::Key(key) => key.clone(),
+    };
+
+    let cpi_accounts = TokenMetadataUpdateField {
+        token_program_id: ctx.accounts.token_program.to_account_info(),
+        metadata: ctx.accounts.mint.to_account_info(),
+        update_authority: ctx.accounts.update_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_metadata_update_field(cpi_ctx, field.into(), value.clone())?;
+
+    // The token program reallocs the mint when the TLV data grows
+    ctx.accounts.top_up_rent()?;
+
+    emit!(MetadataFieldUpdated {
+        mint: ctx.accounts.mint.key(),
+        field: field_name,
+        value,
+    });
+    Ok(())
+}
+
+// Remove a custom key from the mint's additional metadata
+pub fn remove_metadata_key(
+    ctx: Context<UpdateTokenMetadata>,
+    key: String,
+    idempotent: bool
+) -> Result<()> {
+    let ix = remove_key(
+        ctx.accounts.token_program.key,
+        &ctx.accounts.mint.key(),
+        ctx.accounts.update_authority.key,
+        key.clone(),
+        idempotent
+    );
+    invoke(
+        &ix,
+        &[
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.update_authority.to_account_info(),
+        ]
+    )?;
+    ctx.accounts.top_up_rent()?;
+
+    emit!(MetadataKeyRemoved {
+        mint: ctx.accounts.mint.key(),
+        key,
+    });
+    Ok(())
+}
+
+// Rotate the metadata update authority, or renounce it with `None`
+pub fn update_metadata_authority(
+    ctx: Context<UpdateTokenMetadata>,
+    new_authority: Option<Pubkey>
+) -> Result<()> {
+    let cpi_accounts = TokenMetadataUpdateAuthority {
+        token_program_id: ctx.accounts.token_program.to_account_info(),
+        metadata: ctx.accounts.mint.to_account_info(),
+        current_authority: ctx.accounts.update_authority.to_account_info(),
+        new_authority: ctx.accounts.update_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_metadata_update_authority(cpi_ctx, OptionalNonZeroPubkey::try_from(new_authority)?)?;
+    ctx.accounts.top_up_rent()?;
+
+    emit!(MetadataAuthorityUpdated {
+        mint: ctx.accounts.mint.key(),
+        old_authority: ctx.accounts.update_authority.key(),
+        new_authority,
+    });
+    Ok(())
+}
+
+impl<'info> UpdateTokenMetadata<'info> {
+    // Keep the mint rent-exempt after its metadata changed size
+    fn top_up_rent(&self) -> Result<()> {
+        update_account_lamports_to_minimum_balance(
+            self.mint.to_account_info(),
+            self.update_authority.to_account_info(),
+            self.system_program.to_account_info()
+        )
+    }
+}
+
+/// TOKEN METADATA ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct UpdateTokenMetadata<'info> {
+    /// Must match the metadata update authority (also pays any extra rent)
+    #[account(mut)]
+    pub update_authority: Signer<'info>,
+    /// The mint that stores its own metadata
+    #[account(mut, extensions::metadata_pointer::metadata_address = mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event]
+pub struct MetadataFieldUpdated {
+    pub mint: Pubkey,
+    pub field: String,
+    pub value: String,
+}
+
+#[event]
+pub struct MetadataKeyRemoved {
+    pub mint: Pubkey,
+    pub key: String,
+}
+
+#[event]
+pub struct MetadataAuthorityUpdated {
+    pub mint: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Option<Pubkey>,
+}
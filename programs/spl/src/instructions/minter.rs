@@ -0,0 +1,197 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::{ set_authority, spl_token_2022::instruction::AuthorityType, SetAuthority },
+    token_interface::{ Mint, Token2022 },
+};
+use std::mem::size_of;
+
+use crate::state::*;
+
+/// MINTER FUNCTIONS
+// Register a minter, or update its allowance, rate limit and enable flag (config authority only)
+pub fn configure_minter(
+    ctx: Context<AddMinter>,
+    allowance: u64,
+    epoch_limit: u64,
+    enabled: bool
+) -> Result<()> {
+    let minter = &mut ctx.accounts.minter;
+    minter.mint = ctx.accounts.mint.key();
+    minter.authority = ctx.accounts.minter_authority.key();
+    minter.allowance = allowance;
+    minter.epoch_limit = epoch_limit;
+    minter.enabled = enabled;
+    minter.bump = ctx.bumps.minter;
+
+    emit!(MinterConfigured {
+        mint: minter.mint,
+        minter: minter.authority,
+        allowance,
+        epoch_limit,
+        enabled,
+        time: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Remove a minter and refund its rent (config authority only)
+pub fn delete_minter(ctx: Context<RemoveMinter>) -> Result<()> {
+    emit!(MinterRemoved {
+        mint: ctx.accounts.mint.key(),
+        minter: ctx.accounts.minter.authority,
+        time: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Permanently revoke the program's mint authority, fixing the supply
+pub fn revoke_program_mint_authority(ctx: Context<RenounceMintAuthority>) -> Result<()> {
+    let mint_key = ctx.accounts.mint.key();
+    let seeds: &[&[u8]] = &[MINT_AUTHORITY_SEED, mint_key.as_ref(), &[ctx.bumps.mint_authority]];
+    let signer_seeds = &[seeds];
+
+    let cpi_accounts = SetAuthority {
+        current_authority: ctx.accounts.mint_authority.to_account_info(),
+        account_or_mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds
+    );
+    set_authority(cpi_ctx, AuthorityType::MintTokens, None)?;
+
+    emit!(MintAuthorityRenounced {
+        mint: mint_key,
+        authority: ctx.accounts.authority.key(),
+        time: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Check a minter may mint `amount` and charge it against its allowance and epoch window
+pub fn consume_minter_allowance(minter: &mut Minter, amount: u64) -> Result<()> {
+    require!(minter.enabled, MinterError::MinterDisabled);
+    require!(amount <= minter.allowance, MinterError::AllowanceExceeded);
+
+    // Start a fresh rate-limit window on the first mint of each epoch
+    let epoch = Clock::get()?.epoch;
+    if minter.epoch != epoch {
+        minter.epoch = epoch;
+        minter.minted_this_epoch = 0;
+    }
+    let minted_this_epoch = minter.minted_this_epoch
+        .checked_add(amount)
+        .ok_or(MinterError::MathOverflow)?;
+    // epoch_limit == 0 means no per-epoch limit
+    require!(
+        minter.epoch_limit == 0 || minted_this_epoch <= minter.epoch_limit,
+        MinterError::EpochLimitExceeded
+    );
+
+    minter.allowance -= amount;
+    minter.minted_this_epoch = minted_this_epoch;
+    Ok(())
+}
+
+/// MINTER ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct AddMinter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [MINT_CONFIG_SEED, mint.key().as_ref()],
+        bump = mint_config.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+    /// CHECK: wallet being granted minting rights
+    pub minter_authority: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + size_of::<Minter>(),
+        seeds = [MINTER_SEED, mint.key().as_ref(), minter_authority.key().as_ref()],
+        bump
+    )]
+    pub minter: Box<Account<'info, Minter>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveMinter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [MINT_CONFIG_SEED, mint.key().as_ref()],
+        bump = mint_config.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [MINTER_SEED, mint.key().as_ref(), minter.authority.as_ref()],
+        bump = minter.bump,
+        has_one = mint
+    )]
+    pub minter: Box<Account<'info, Minter>>,
+}
+
+#[derive(Accounts)]
+pub struct RenounceMintAuthority<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [MINT_CONFIG_SEED, mint.key().as_ref()],
+        bump = mint_config.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+    /// CHECK: program PDA holding the mint authority
+    #[account(seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event]
+pub struct MinterConfigured {
+    pub mint: Pubkey,
+    pub minter: Pubkey,
+    pub allowance: u64,
+    pub epoch_limit: u64,
+    pub enabled: bool,
+    pub time: i64,
+}
+
+#[event]
+pub struct MinterRemoved {
+    pub mint: Pubkey,
+    pub minter: Pubkey,
+    pub time: i64,
+}
+
+#[event]
+pub struct MintAuthorityRenounced {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub time: i64,
+}
+
+#[error_code]
+pub enum MinterError {
+    #[msg("minter is disabled")]
+    MinterDisabled,
+    #[msg("amount exceeds the minter's remaining allowance")]
+    AllowanceExceeded,
+    #[msg("amount exceeds the minter's per-epoch limit")]
+    EpochLimitExceeded,
+    #[msg("math overflow")]
+    MathOverflow,
+}
@@ -1,6 +1,40 @@
+pub mod account_extension;
+pub mod airdrop;
 pub mod amm;
+pub mod authority;
+pub mod batch;
+pub mod confidential_transfer;
+pub mod delegation;
+pub mod extension_report;
+pub mod group;
+pub mod interest_bearing;
+pub mod kyc;
+pub mod metadata;
+pub mod minter;
+pub mod multisig;
+pub mod permanent_delegate;
 pub mod staking;
+pub mod supply;
 pub mod token;
+pub mod transfer_fee;
+pub mod vesting;
+pub use account_extension::*;
+pub use airdrop::*;
 pub use amm::*;
+pub use authority::*;
+pub use batch::*;
+pub use confidential_transfer::*;
+pub use delegation::*;
+pub use extension_report::*;
+pub use group::*;
+pub use interest_bearing::*;
+pub use kyc::*;
+pub use metadata::*;
+pub use minter::*;
+pub use multisig::*;
+pub use permanent_delegate::*;
 pub use staking::*;
+pub use supply::*;
 pub use token::*;
+pub use transfer_fee::*;
+pub use vesting::*;
@@ -0,0 +1,542 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::{ burn, freeze_account, mint_to, Burn, FreezeAccount, MintTo },
+    token_2022_extensions::{ token_metadata_update_field, TokenMetadataUpdateField },
+    token_interface::{ Mint, Token2022, TokenAccount },
+};
+
+use crate::{
+    instructions::{ check_supply_cap, consume_minter_allowance, MinterConfigured },
+    state::*,
+    utils::*,
+};
+
+/// MULTISIG FUNCTIONS
+// Create an M-of-N multisig that can act as a token authority
+pub fn initialize_multisig(
+    ctx: Context<CreateMultisig>,
+    signers: Vec<Pubkey>,
+    threshold: u8
+) -> Result<()> {
+    require!(signers.len() <= MAX_MULTISIG_SIGNERS, MultisigError::TooManySigners);
+    require!(
+        threshold > 0 && (threshold as usize) <= signers.len(),
+        MultisigError::InvalidThreshold
+    );
+    for (i, signer) in signers.iter().enumerate() {
+        require!(!signers[..i].contains(signer), MultisigError::DuplicateSigner);
+    }
+
+    let multisig = &mut ctx.accounts.multisig;
+    multisig.create_key = ctx.accounts.create_key.key();
+    multisig.signers = signers.clone();
+    multisig.threshold = threshold;
+    multisig.proposal_count = 0;
+    multisig.bump = ctx.bumps.multisig;
+
+    emit!(MultisigCreated {
+        multisig: multisig.key(),
+        signers,
+        threshold,
+    });
+    Ok(())
+}
+
+// Propose an action; the proposer's approval is recorded immediately
+pub fn propose_action(
+    ctx: Context<CreateProposal>,
+    action: MultisigAction,
+    expires_at: i64
+) -> Result<()> {
+    let multisig = &mut ctx.accounts.multisig;
+    let proposer = ctx.accounts.proposer.key();
+    require!(multisig.signers.contains(&proposer), MultisigError::NotASigner);
+    require!(expires_at > Clock::get()?.unix_timestamp, MultisigError::InvalidExpiry);
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.multisig = multisig.key();
+    proposal.proposer = proposer;
+    proposal.index = multisig.proposal_count;
+    proposal.action = action;
+    proposal.approvals = vec![proposer];
+    proposal.expires_at = expires_at;
+    proposal.executed = false;
+    proposal.bump = ctx.bumps.proposal;
+
+    multisig.proposal_count = multisig.proposal_count
+        .checked_add(1)
+        .ok_or(MultisigError::MathOverflow)?;
+
+    emit!(ProposalCreated {
+        multisig: proposal.multisig,
+        proposal: proposal.key(),
+        proposer,
+        index: proposal.index,
+        expires_at,
+    });
+    Ok(())
+}
+
+// Approve a pending proposal
+pub fn approve_action(ctx: Context<ApproveProposal>) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+    require!(ctx.accounts.multisig.signers.contains(&signer), MultisigError::NotASigner);
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.check_pending()?;
+    require!(!proposal.approvals.contains(&signer), MultisigError::AlreadyApproved);
+    proposal.approvals.push(signer);
+
+    emit!(ProposalApproved {
+        proposal: proposal.key(),
+        signer,
+        approvals: proposal.approvals.len() as u8,
+    });
+    Ok(())
+}
+
+// Execute a proposal once it has reached the threshold, signing as the multisig
+pub fn execute_action(ctx: Context<ExecuteProposal>) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+    proposal.check_pending()?;
+    require!(
+        proposal.approvals.len() >= (ctx.accounts.multisig.threshold as usize),
+        MultisigError::NotEnoughApprovals
+    );
+    require_keys_eq!(proposal.action.mint(), ctx.accounts.mint.key(), MultisigError::AccountMismatch);
+
+    match proposal.action.clone() {
+        MultisigAction::MintTokens { to, amount, .. } => {
+            let bump = ctx.bumps.mint_authority.ok_or(MultisigError::MissingAccount)?;
+            ctx.accounts.mint_tokens(to, amount, bump)?
+        }
+        MultisigAction::BurnTokens { from, amount, .. } => ctx.accounts.burn_tokens(from, amount)?,
+        MultisigAction::FreezeTokenAccount { account, .. } => {
+            ctx.accounts.freeze_token_account(account)?
+        }
+        MultisigAction::UpdateMetadataField { field, value, .. } => {
+            ctx.accounts.update_metadata_field(field, value)?
+        }
+        MultisigAction::ConfigureMinter { minter, allowance, epoch_limit, enabled, .. } => {
+            ctx.accounts.configure_minter(minter, allowance, epoch_limit, enabled)?
+        }
+        MultisigAction::TransferConfigAuthority { new_authority, .. } => {
+            ctx.accounts.transfer_config_authority(new_authority)?
+        }
+    }
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.executed = true;
+
+    emit!(ProposalExecuted {
+        multisig: proposal.multisig,
+        proposal: proposal.key(),
+        executor: ctx.accounts.executor.key(),
+        time: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Close an executed or expired proposal and refund its rent to the proposer
+pub fn close_settled_proposal(ctx: Context<CloseProposal>) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+    require!(
+        proposal.executed || Clock::get()?.unix_timestamp >= proposal.expires_at,
+        MultisigError::ProposalPending
+    );
+
+    emit!(ProposalClosed {
+        multisig: proposal.multisig,
+        proposal: proposal.key(),
+        proposer: proposal.proposer,
+        executed: proposal.executed,
+    });
+    Ok(())
+}
+
+// Hand a mint's config authority (minters and supply cap) to a multisig
+pub fn transfer_config_authority_to_multisig(ctx: Context<TransferConfigAuthority>) -> Result<()> {
+    let mint_config = &mut ctx.accounts.mint_config;
+    mint_config.authority = ctx.accounts.multisig.key();
+
+    emit!(ConfigAuthorityTransferred {
+        mint: mint_config.mint,
+        old_authority: ctx.accounts.authority.key(),
+        new_authority: mint_config.authority,
+        time: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+impl Proposal {
+    // Proposals can only be approved or executed once, before they expire
+    fn check_pending(&self) -> Result<()> {
+        require!(!self.executed, MultisigError::AlreadyExecuted);
+        require!(Clock::get()?.unix_timestamp < self.expires_at, MultisigError::ProposalExpired);
+        Ok(())
+    }
+}
+
+impl<'info> ExecuteProposal<'info> {
+    // Token account named by the proposal
+    fn token_account(&self, expected: Pubkey) -> Result<AccountInfo<'info>> {
+        let account = self.token_account.as_ref().ok_or(MultisigError::MissingAccount)?;
+        require_keys_eq!(account.key(), expected, MultisigError::AccountMismatch);
+        Ok(account.to_account_info())
+    }
+
+    // Mint through the multisig's minter entry, signed by the program mint authority
+    fn mint_tokens(&mut self, to: Pubkey, amount: u64, mint_authority_bump: u8) -> Result<()> {
+        let to = self.token_account(to)?;
+        let mint_config = self.mint_config.as_ref().ok_or(MultisigError::MissingAccount)?;
+        let mint_authority = self.mint_authority.as_ref().ok_or(MultisigError::MissingAccount)?;
+        let minter = self.minter.as_mut().ok_or(MultisigError::MissingAccount)?;
+        require_keys_eq!(minter.authority, self.multisig.key(), MultisigError::AccountMismatch);
+
+        check_supply_cap(mint_config, self.mint.supply, amount)?;
+        consume_minter_allowance(minter, amount)?;
+
+        let mint_key = self.mint.key();
+        let seeds: &[&[u8]] = &[MINT_AUTHORITY_SEED, mint_key.as_ref(), &[mint_authority_bump]];
+        let signer_seeds = &[seeds];
+
+        let cpi_accounts = MintTo {
+            mint: self.mint.to_account_info(),
+            to,
+            authority: mint_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds
+        );
+        mint_to(cpi_ctx, amount)
+    }
+
+    // Burn from a token account owned by the multisig
+    fn burn_tokens(&self, from: Pubkey, amount: u64) -> Result<()> {
+        let cpi_accounts = Burn {
+            mint: self.mint.to_account_info(),
+            from: self.token_account(from)?,
+            authority: self.multisig.to_account_info(),
+        };
+        let seeds: &[&[u8]] = &[
+            MULTISIG_SEED,
+            self.multisig.create_key.as_ref(),
+            &[self.multisig.bump],
+        ];
+        let signer_seeds = &[seeds];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds
+        );
+        burn(cpi_ctx, amount)
+    }
+
+    // Freeze a token account with the multisig as freeze authority
+    fn freeze_token_account(&self, account: Pubkey) -> Result<()> {
+        let cpi_accounts = FreezeAccount {
+            account: self.token_account(account)?,
+            mint: self.mint.to_account_info(),
+            authority: self.multisig.to_account_info(),
+        };
+        let seeds: &[&[u8]] = &[
+            MULTISIG_SEED,
+            self.multisig.create_key.as_ref(),
+            &[self.multisig.bump],
+        ];
+        let signer_seeds = &[seeds];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds
+        );
+        freeze_account(cpi_ctx)
+    }
+
+    // Update a metadata field with the multisig as update authority; the executor covers any extra rent
+    fn update_metadata_field(&self, field: MetadataField, value: String) -> Result<()> {
+        let cpi_accounts = TokenMetadataUpdateField {
+            token_program_id: self.token_program.to_account_info(),
+            metadata: self.mint.to_account_info(),
+            update_authority: self.multisig.to_account_info(),
+        };
+        let seeds: &[&[u8]] = &[
+            MULTISIG_SEED,
+            self.multisig.create_key.as_ref(),
+            &[self.multisig.bump],
+        ];
+        let signer_seeds = &[seeds];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds
+        );
+        token_metadata_update_field(cpi_ctx, field.into(), value)?;
+
+        update_account_lamports_to_minimum_balance(
+            self.mint.to_account_info(),
+            self.executor.to_account_info(),
+            self.system_program.to_account_info()
+        )
+    }
+
+    // Mint config the multisig administers as config authority
+    fn mint_config_as_authority(&mut self) -> Result<&mut MintConfig> {
+        let multisig = self.multisig.key();
+        let mint_config = self.mint_config.as_mut().ok_or(MultisigError::MissingAccount)?;
+        require_keys_eq!(mint_config.authority, multisig, MultisigError::NotConfigAuthority);
+        Ok(mint_config)
+    }
+
+    // Update an existing minter entry with the multisig as config authority
+    fn configure_minter(
+        &mut self,
+        minter_authority: Pubkey,
+        allowance: u64,
+        epoch_limit: u64,
+        enabled: bool
+    ) -> Result<()> {
+        self.mint_config_as_authority()?;
+        let minter = self.minter.as_mut().ok_or(MultisigError::MissingAccount)?;
+        require_keys_eq!(minter.authority, minter_authority, MultisigError::AccountMismatch);
+        minter.allowance = allowance;
+        minter.epoch_limit = epoch_limit;
+        minter.enabled = enabled;
+
+        emit!(MinterConfigured {
+            mint: minter.mint,
+            minter: minter.authority,
+            allowance,
+            epoch_limit,
+            enabled,
+            time: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    // Hand the config authority on to a wallet or another multisig
+    fn transfer_config_authority(&mut self, new_authority: Pubkey) -> Result<()> {
+        let old_authority = self.multisig.key();
+        let mint_config = self.mint_config_as_authority()?;
+        mint_config.authority = new_authority;
+
+        emit!(ConfigAuthorityTransferred {
+            mint: mint_config.mint,
+            old_authority,
+            new_authority,
+            time: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+}
+
+/// MULTISIG ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+#[instruction(signers: Vec<Pubkey>)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// One-off key that makes the multisig address unique
+    pub create_key: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = Multisig::space(signers.len()),
+        seeds = [MULTISIG_SEED, create_key.key().as_ref()],
+        bump
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(action: MultisigAction)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [MULTISIG_SEED, multisig.create_key.as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::space(&action, multisig.signers.len()),
+        seeds = [
+            PROPOSAL_SEED,
+            multisig.key().as_ref(),
+            &multisig.proposal_count.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    pub signer: Signer<'info>,
+    #[account(seeds = [MULTISIG_SEED, multisig.create_key.as_ref()], bump = multisig.bump)]
+    pub multisig: Box<Account<'info, Multisig>>,
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, multisig.key().as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+        has_one = multisig
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    /// Anyone may execute an approved proposal (and pays any metadata rent top-up)
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    #[account(seeds = [MULTISIG_SEED, multisig.create_key.as_ref()], bump = multisig.bump)]
+    pub multisig: Box<Account<'info, Multisig>>,
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, multisig.key().as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+        has_one = multisig
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// Token account targeted by mint, burn and freeze proposals
+    #[account(mut, token::mint = mint)]
+    pub token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    /// The multisig's minter entry for mint proposals, or the entry a minter proposal updates
+    #[account(
+        mut,
+        seeds = [MINTER_SEED, mint.key().as_ref(), minter.authority.as_ref()],
+        bump = minter.bump
+    )]
+    pub minter: Option<Box<Account<'info, Minter>>>,
+    /// Supply cap for mint proposals; the config minter and authority proposals update
+    #[account(mut, seeds = [MINT_CONFIG_SEED, mint.key().as_ref()], bump = mint_config.bump)]
+    pub mint_config: Option<Box<Account<'info, MintConfig>>>,
+    /// CHECK: program PDA holding the mint authority, required for mint proposals
+    #[account(seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()], bump)]
+    pub mint_authority: Option<UncheckedAccount<'info>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct CloseProposal<'info> {
+    /// CHECK: the proposal's creator, refunded its rent
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(seeds = [MULTISIG_SEED, multisig.create_key.as_ref()], bump = multisig.bump)]
+    pub multisig: Box<Account<'info, Multisig>>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [PROPOSAL_SEED, multisig.key().as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+        has_one = multisig,
+        has_one = proposer
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+}
+
+#[derive(Accounts)]
+pub struct TransferConfigAuthority<'info> {
+    pub authority: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [MINT_CONFIG_SEED, mint.key().as_ref()],
+        bump = mint_config.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+    /// Multisig that becomes the config authority
+    #[account(seeds = [MULTISIG_SEED, multisig.create_key.as_ref()], bump = multisig.bump)]
+    pub multisig: Box<Account<'info, Multisig>>,
+}
+
+#[event]
+pub struct MultisigCreated {
+    pub multisig: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    pub index: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub proposal: Pubkey,
+    pub signer: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub executor: Pubkey,
+    pub time: i64,
+}
+
+#[event]
+pub struct ProposalClosed {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    pub executed: bool,
+}
+
+#[event]
+pub struct ConfigAuthorityTransferred {
+    pub mint: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub time: i64,
+}
+
+#[error_code]
+pub enum MultisigError {
+    #[msg("threshold must be between 1 and the number of signers")]
+    InvalidThreshold,
+    #[msg("too many multisig signers")]
+    TooManySigners,
+    #[msg("duplicate multisig signer")]
+    DuplicateSigner,
+    #[msg("signer is not a member of the multisig")]
+    NotASigner,
+    #[msg("signer has already approved this proposal")]
+    AlreadyApproved,
+    #[msg("proposal has expired")]
+    ProposalExpired,
+    #[msg("proposal has already been executed")]
+    AlreadyExecuted,
+    #[msg("proposal has not reached the approval threshold")]
+    NotEnoughApprovals,
+    #[msg("expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("account does not match the proposal")]
+    AccountMismatch,
+    #[msg("account required by the proposal is missing")]
+    MissingAccount,
+    #[msg("proposal is still pending")]
+    ProposalPending,
+    #[msg("multisig is not the mint config authority")]
+    NotConfigAuthority,
+    #[msg("math overflow")]
+    MathOverflow,
+}
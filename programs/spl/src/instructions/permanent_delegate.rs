@@ -0,0 +1,228 @@
+use anchor_lang::{ prelude::*, solana_program::program_option::COption };
+use anchor_spl::{
+    token_2022::{ freeze_account, thaw_account, FreezeAccount, ThawAccount, TransferChecked },
+    token_interface::{ Mint, Token2022, TokenAccount },
+};
+
+use crate::{ state::*, utils::* };
+
+/// PERMANENT DELEGATE FUNCTIONS
+// Recover tokens from a holder into an account owned by the permanent delegate
+pub fn clawback_tokens<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Clawback<'info>>,
+    amount: u64,
+    reason_code: u16
+) -> Result<()> {
+    delegate_transfer(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        &ctx.accounts.source,
+        &ctx.accounts.destination,
+        &ctx.accounts.delegate,
+        ctx.accounts.freeze_authority.as_ref(),
+        ctx.remaining_accounts,
+        amount
+    )?;
+
+    emit!(ClawbackExecuted {
+        mint: ctx.accounts.mint.key(),
+        source: ctx.accounts.source.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+        reason_code,
+        time: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Move tokens from a holder to any account of the same mint
+pub fn force_transfer_tokens<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ForceTransfer<'info>>,
+    amount: u64,
+    reason_code: u16
+) -> Result<()> {
+    delegate_transfer(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        &ctx.accounts.source,
+        &ctx.accounts.destination,
+        &ctx.accounts.delegate,
+        ctx.accounts.freeze_authority.as_ref(),
+        ctx.remaining_accounts,
+        amount
+    )?;
+
+    emit!(ForceTransferExecuted {
+        mint: ctx.accounts.mint.key(),
+        source: ctx.accounts.source.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+        reason_code,
+        time: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Transfer signed by the permanent delegate on behalf of the source owner (`hook_accounts` are
+// the transfer hook's extra accounts for hooked mints). A frozen source is thawed and frozen
+// again around the transfer by `freeze_authority`: a co-signer, or the KYC freeze PDA.
+#[allow(clippy::too_many_arguments)]
+fn delegate_transfer<'info>(
+    token_program: &Program<'info, Token2022>,
+    mint: &InterfaceAccount<'info, Mint>,
+    source: &InterfaceAccount<'info, TokenAccount>,
+    destination: &InterfaceAccount<'info, TokenAccount>,
+    permanent_delegate: &Signer<'info>,
+    freeze_authority: Option<&UncheckedAccount<'info>>,
+    hook_accounts: &[AccountInfo<'info>],
+    amount: u64
+) -> Result<()> {
+    require!(amount > 0, PermanentDelegateError::ZeroAmount);
+    require!(source.amount >= amount, PermanentDelegateError::InsufficientBalance);
+
+    let frozen = source.is_frozen();
+    let freeze_authority = match freeze_authority {
+        Some(freeze_authority) if frozen => Some(freeze_authority),
+        _ => {
+            require!(!frozen, PermanentDelegateError::SourceFrozen);
+            None
+        }
+    };
+    let kyc_freeze_bump = match freeze_authority {
+        Some(freeze_authority) => kyc_freeze_bump(mint, freeze_authority)?,
+        None => None,
+    };
+    let mint_key = mint.key();
+    let bump = [kyc_freeze_bump.unwrap_or_default()];
+    let kyc_seeds: &[&[u8]] = &[KYC_FREEZE_AUTHORITY_SEED, mint_key.as_ref(), &bump];
+    let freeze_seeds: &[&[&[u8]]] = if kyc_freeze_bump.is_some() { &[kyc_seeds] } else { &[] };
+
+    if let Some(freeze_authority) = freeze_authority {
+        let cpi_accounts = ThawAccount {
+            account: source.to_account_info(),
+            mint: mint.to_account_info(),
+            authority: freeze_authority.to_account_info(),
+        };
+        thaw_account(
+            CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, freeze_seeds)
+        )?;
+    }
+
+    let cpi_accounts = TransferChecked {
+        from: source.to_account_info(),
+        to: destination.to_account_info(),
+        authority: permanent_delegate.to_account_info(),
+        mint: mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(
+        token_program.to_account_info(),
+        cpi_accounts
+    ).with_remaining_accounts(hook_accounts.to_vec());
+    transfer_checked_with_hook(cpi_ctx, amount, mint.decimals)?;
+
+    // Leave the holder frozen as it was found
+    if let Some(freeze_authority) = freeze_authority {
+        let cpi_accounts = FreezeAccount {
+            account: source.to_account_info(),
+            mint: mint.to_account_info(),
+            authority: freeze_authority.to_account_info(),
+        };
+        freeze_account(
+            CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, freeze_seeds)
+        )?;
+    }
+    Ok(())
+}
+
+// Check `freeze_authority` holds the mint's freeze authority; returns the bump to sign with
+// when it is the program's KYC freeze PDA rather than a co-signer
+fn kyc_freeze_bump(mint: &InterfaceAccount<Mint>, freeze_authority: &UncheckedAccount) -> Result<Option<u8>> {
+    require!(
+        mint.freeze_authority == COption::Some(freeze_authority.key()),
+        PermanentDelegateError::FreezeAuthorityMismatch
+    );
+    if freeze_authority.is_signer {
+        return Ok(None);
+    }
+    let (kyc_freeze_authority, bump) = Pubkey::find_program_address(
+        &[KYC_FREEZE_AUTHORITY_SEED, mint.key().as_ref()],
+        &crate::ID
+    );
+    require_keys_eq!(
+        kyc_freeze_authority,
+        freeze_authority.key(),
+        PermanentDelegateError::FreezeAuthorityNotSigner
+    );
+    Ok(Some(bump))
+}
+
+/// PERMANENT DELEGATE ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct Clawback<'info> {
+    /// Must match the mint's permanent delegate
+    pub delegate: Signer<'info>,
+    #[account(extensions::permanent_delegate::delegate = delegate)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// Holder account the tokens are recovered from
+    #[account(mut, token::mint = mint)]
+    pub source: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Recovery account owned by the permanent delegate
+    #[account(mut, token::mint = mint, token::authority = delegate)]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: the mint's freeze authority, only needed when `source` is frozen; checked in
+    /// the handler (a co-signer, or the program's KYC freeze PDA)
+    pub freeze_authority: Option<UncheckedAccount<'info>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct ForceTransfer<'info> {
+    /// Must match the mint's permanent delegate
+    pub delegate: Signer<'info>,
+    #[account(extensions::permanent_delegate::delegate = delegate)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// Holder account the tokens are moved from
+    #[account(mut, token::mint = mint)]
+    pub source: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Account designated to receive the tokens
+    #[account(mut, token::mint = mint)]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: the mint's freeze authority, only needed when `source` is frozen; checked in
+    /// the handler (a co-signer, or the program's KYC freeze PDA)
+    pub freeze_authority: Option<UncheckedAccount<'info>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event]
+pub struct ClawbackExecuted {
+    pub mint: Pubkey,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub reason_code: u16,
+    pub time: i64,
+}
+
+#[event]
+pub struct ForceTransferExecuted {
+    pub mint: Pubkey,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub reason_code: u16,
+    pub time: i64,
+}
+
+#[error_code]
+pub enum PermanentDelegateError {
+    #[msg("amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("source account balance is too low")]
+    InsufficientBalance,
+    #[msg("source account is frozen; pass the mint's freeze authority to recover funds")]
+    SourceFrozen,
+    #[msg("account is not the mint's freeze authority")]
+    FreezeAuthorityMismatch,
+    #[msg("freeze authority must sign")]
+    FreezeAuthorityNotSigner,
+}
@@ -2,12 +2,18 @@ use anchor_lang::prelude::*;
 
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_2022::{ mint_to, transfer_checked, MintTo, TransferChecked },
-    token_interface::{ Mint, Token2022, TokenAccount },
+    token_interface::{
+        mint_to,
+        Mint,
+        MintTo,
+        TokenAccount,
+        TokenInterface,
+        TransferChecked,
+    },
 };
 use std::mem::size_of;
 
-use crate::state::*;
+use crate::{ state::*, utils::* };
 
 // Precision for reward calculations (9 decimals)
 const PRECISION: u128 = 1_000_000_000;
@@ -19,6 +25,10 @@ const LOCKUP_PERIOD: i64 = 5;
 /// STAKING POOL FUNCTIONS
 // Initialize a new staking pool
 pub fn initialize_pool(ctx: Context<InitializePool>, reward_rate_per_day: u128) -> Result<()> {
+    require!(
+        !is_non_transferable_mint(&ctx.accounts.stake_mint.to_account_info())?,
+        StakingError::NonTransferableMint
+    );
     let pool = &mut ctx.accounts.pool;
     pool.stake_mint = ctx.accounts.stake_mint.key();
     pool.reward_mint = ctx.accounts.reward_mint.key();
@@ -39,8 +49,15 @@ pub fn initialize_pool(ctx: Context<InitializePool>, reward_rate_per_day: u128)
 }
 
 // Stake tokens into the pool
-pub fn stake_tokens(ctx: Context<Stake>, amount: u64) -> Result<()> {
+pub fn stake_tokens<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Stake<'info>>,
+    amount: u64
+) -> Result<()> {
     require!(!ctx.accounts.pool.paused, StakingError::PoolPaused);
+    require!(
+        !is_non_transferable_mint(&ctx.accounts.stake_mint.to_account_info())?,
+        StakingError::NonTransferableMint
+    );
 
     let pool: &mut Account<'_, StakingPool> = &mut ctx.accounts.pool;
     let user = &mut ctx.accounts.user_stake;
@@ -83,8 +100,11 @@ pub fn stake_tokens(ctx: Context<Stake>, amount: u64) -> Result<()> {
         authority: ctx.accounts.staker.to_account_info(),
         mint: ctx.accounts.stake_mint.to_account_info(),
     };
-    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-    transfer_checked(cpi_ctx, amount, mint_decimals)?;
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts
+    ).with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(cpi_ctx, amount, mint_decimals)?;
 
     // Update balances and reward debt
     pool.total_staked = pool.total_staked.checked_add(amount as u128).unwrap();
@@ -104,7 +124,10 @@ pub fn stake_tokens(ctx: Context<Stake>, amount: u64) -> Result<()> {
 }
 
 // Unstake tokens from the pool
-pub fn unstake_tokens(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+pub fn unstake_tokens<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>,
+    amount: u64
+) -> Result<()> {
     require!(!ctx.accounts.pool.paused, StakingError::PoolPaused);
     let user = &mut ctx.accounts.user_stake;
     let now = Clock::get()?.unix_timestamp;
@@ -120,7 +143,7 @@ pub fn unstake_tokens(ctx: Context<Unstake>, amount: u64) -> Result<()> {
         ctx.program_id
     );
     let seeds: &[&[u8]] = &[POOL_SEED, stake_mint_key.as_ref(), &[pool_bump]];
-    let signer_seeds = &[&seeds[..]];
+    let signer_seeds = &[seeds];
 
     let pool = &mut ctx.accounts.pool;
 
@@ -165,8 +188,8 @@ pub fn unstake_tokens(ctx: Context<Unstake>, amount: u64) -> Result<()> {
         ctx.accounts.token_program.to_account_info(),
         cpi_accounts,
         signer_seeds
-    );
-    transfer_checked(cpi_ctx, amount, ctx.accounts.stake_mint.decimals)?;
+    ).with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(cpi_ctx, amount, ctx.accounts.stake_mint.decimals)?;
 
     // Pay out pending rewards if any
     if user.pending_rewards > 0 {
@@ -185,8 +208,8 @@ pub fn unstake_tokens(ctx: Context<Unstake>, amount: u64) -> Result<()> {
             ctx.accounts.token_program.to_account_info(),
             cpi_accounts,
             signer_seeds
-        );
-        transfer_checked(cpi_ctx, reward_amount, ctx.accounts.reward_mint.decimals)?;
+        ).with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        transfer_checked_with_hook(cpi_ctx, reward_amount, ctx.accounts.reward_mint.decimals)?;
         user.pending_rewards = 0;
     }
 
@@ -206,7 +229,9 @@ pub fn unstake_tokens(ctx: Context<Unstake>, amount: u64) -> Result<()> {
 }
 
 // Claim accumulated rewards
-pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+pub fn claim_rewards<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>
+) -> Result<()> {
     require!(!ctx.accounts.pool.paused, StakingError::PoolPaused);
     let mut reward_amount: u64 = 0;
     let pool_account_info: AccountInfo<'_> = ctx.accounts.pool.to_account_info();
@@ -218,7 +243,7 @@ pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
         ctx.program_id
     );
     let seeds: &[&[u8]] = &[POOL_SEED, stake_mint_key.as_ref(), &[pool_bump]];
-    let signer_seeds = &[&seeds[..]];
+    let signer_seeds = &[seeds];
 
     let pool = &mut ctx.accounts.pool;
     let user = &mut ctx.accounts.user_stake;
@@ -263,8 +288,8 @@ pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
             ctx.accounts.token_program.to_account_info(),
             cpi_accounts,
             signer_seeds
-        );
-        transfer_checked(cpi_ctx, reward_amount, ctx.accounts.reward_mint.decimals)?;
+        ).with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        transfer_checked_with_hook(cpi_ctx, reward_amount, ctx.accounts.reward_mint.decimals)?;
         user.pending_rewards = 0;
     }
 
@@ -368,7 +393,9 @@ fn pending_reward(
 }
 
 // Emergency withdraw with 10% penalty
-pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+pub fn emergency_withdraw<'info>(
+    ctx: Context<'_, '_, 'info, 'info, EmergencyWithdraw<'info>>
+) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
     let stake_mint_key = ctx.accounts.stake_mint.key();
     let user = &mut ctx.accounts.user_stake;
@@ -402,8 +429,8 @@ pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
             mint: ctx.accounts.stake_mint.to_account_info(),
         },
         signer_seeds
-    );
-    transfer_checked(cpi_ctx, payout, ctx.accounts.stake_mint.decimals)?;
+    ).with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(cpi_ctx, payout, ctx.accounts.stake_mint.decimals)?;
 
     // Reset user state
     pool.total_staked = pool.total_staked.saturating_sub(user.amount_staked);
@@ -425,6 +452,8 @@ pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
 pub struct InitializePool<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
+    /// Classic SPL Token or Token-2022 mint; the vaults are created under its program
+    #[account(mint::token_program = token_program)]
     pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(mut, constraint = reward_mint.key() == stake_mint.key())]
     pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
@@ -456,7 +485,7 @@ pub struct InitializePool<'info> {
     )]
     pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token2022>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -496,7 +525,7 @@ pub struct Stake<'info> {
     pub user_stake: Account<'info, UserStake>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token2022>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -541,7 +570,7 @@ pub struct Unstake<'info> {
 
     pub system_program: Program<'info, System>,
 
-    pub token_program: Program<'info, Token2022>,
+    pub token_program: Interface<'info, TokenInterface>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
 
@@ -578,7 +607,7 @@ pub struct ClaimRewards<'info> {
     #[account(mut)]
     pub user_reward_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    pub token_program: Program<'info, Token2022>,
+    pub token_program: Interface<'info, TokenInterface>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
@@ -609,7 +638,7 @@ pub struct DepositRewards<'info> {
     pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut)]
     pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
-    pub token_program: Program<'info, Token2022>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
@@ -647,7 +676,7 @@ pub struct EmergencyWithdraw<'info> {
     )]
     pub user_stake: Account<'info, UserStake>,
 
-    pub token_program: Program<'info, Token2022>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[event]
@@ -699,4 +728,6 @@ pub enum StakingError {
     PoolPaused,
     #[msg("Cannot unstake before lock-up expires.")]
     LockupNotExpired,
+    #[msg("Non-transferable mints cannot be staked.")]
+    NonTransferableMint,
 }
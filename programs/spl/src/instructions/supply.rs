@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::*;
+
+/// SUPPLY CAP FUNCTIONS
+// Lower the hard supply cap of a mint (config authority only, never raised)
+pub fn set_max_supply(ctx: Context<UpdateMaxSupply>, max_supply: u64) -> Result<()> {
+    let mint_config = &mut ctx.accounts.mint_config;
+    require!(max_supply <= mint_config.max_supply, SupplyError::MaxSupplyRaised);
+    require!(max_supply >= ctx.accounts.mint.supply, SupplyError::MaxSupplyBelowSupply);
+
+    let old_max_supply = mint_config.max_supply;
+    mint_config.max_supply = max_supply;
+
+    emit!(MaxSupplyLowered {
+        mint: ctx.accounts.mint.key(),
+        authority: ctx.accounts.authority.key(),
+        old_max_supply,
+        new_max_supply: max_supply,
+        time: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Amount that can still be minted before the cap is reached
+pub fn calculate_remaining_supply(ctx: Context<RemainingSupply>) -> Result<u64> {
+    Ok(ctx.accounts.mint_config.remaining_supply(ctx.accounts.mint.supply))
+}
+
+// Check that minting `amount` keeps the mint within its supply cap
+pub fn check_supply_cap(mint_config: &MintConfig, supply: u64, amount: u64) -> Result<()> {
+    let new_supply = supply.checked_add(amount).ok_or(SupplyError::MathOverflow)?;
+    require!(new_supply <= mint_config.max_supply, SupplyError::MaxSupplyExceeded);
+    Ok(())
+}
+
+/// SUPPLY CAP ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct UpdateMaxSupply<'info> {
+    pub authority: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [MINT_CONFIG_SEED, mint.key().as_ref()],
+        bump = mint_config.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct RemainingSupply<'info> {
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(seeds = [MINT_CONFIG_SEED, mint.key().as_ref()], bump = mint_config.bump)]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+}
+
+#[event]
+pub struct MaxSupplyLowered {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub old_max_supply: u64,
+    pub new_max_supply: u64,
+    pub time: i64,
+}
+
+#[error_code]
+pub enum SupplyError {
+    #[msg("mint would exceed its maximum supply")]
+    MaxSupplyExceeded,
+    #[msg("maximum supply can only be lowered")]
+    MaxSupplyRaised,
+    #[msg("maximum supply cannot be below the current supply")]
+    MaxSupplyBelowSupply,
+    #[msg("math overflow")]
+    MathOverflow,
+}
@@ -1,41 +1,190 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    solana_program::{ program::invoke, program_option::COption },
+    system_program::{ create_account, CreateAccount },
+};
 
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    token_2022::{
+    associated_token::{
+        create_idempotent,
+        get_associated_token_address_with_program_id,
+        AssociatedToken,
+        Create,
+    },
+    token_2022::{ initialize_mint2, InitializeMint2 },
+    token_2022_extensions::{
+        default_account_state_initialize,
+        group_member_pointer_initialize,
+        group_pointer_initialize,
+        interest_bearing_mint_initialize,
+        metadata_pointer_initialize,
+        mint_close_authority_initialize,
+        non_transferable_mint_initialize,
+        permanent_delegate_initialize,
+        transfer_fee_initialize,
+        transfer_hook_initialize,
+        DefaultAccountStateInitialize,
+        GroupMemberPointerInitialize,
+        GroupPointerInitialize,
+        InterestBearingMintInitialize,
+        MetadataPointerInitialize,
+        MintCloseAuthorityInitialize,
+        NonTransferableMintInitialize,
+        PermanentDelegateInitialize,
+        TransferFeeInitialize,
+        TransferHookInitialize,
+    },
+    token_interface::{
         burn,
         close_account,
+        find_mint_account_size,
         freeze_account,
         mint_to,
+        spl_token_2022::{
+            extension::{
+                confidential_transfer::instruction::initialize_mint as confidential_transfer_initialize,
+                transfer_fee::MAX_FEE_BASIS_POINTS,
+                ExtensionType,
+            },
+            solana_zk_token_sdk::zk_token_elgamal::pod::ElGamalPubkey,
+            state::AccountState,
+        },
         thaw_account,
+        token_metadata_initialize,
         Burn,
         CloseAccount,
         FreezeAccount,
+        Mint,
         MintTo,
         ThawAccount,
-    },
-    token_interface::{
-        token_metadata_initialize,
-        Mint,
         Token2022,
         TokenAccount,
+        TokenInterface,
         TokenMetadataInitialize,
     },
 };
+use spl_hook::{
+    cpi::accounts::{ CloseHookAccounts, InitializeHook },
+    program::SplHook,
+    APPROVE_ACCOUNT_SEED,
+    META_LIST_ACCOUNT_SEED,
+    MINT_POLICY_SEED,
+    VELOCITY_CONFIG_SEED,
+};
+use std::mem::size_of;
 
-use crate::utils::*;
+use crate::{
+    instructions::{
+        check_supply_cap,
+        consume_minter_allowance,
+        ConfidentialTransferArgs,
+        ConfidentialTransferError,
+        TransferFeeError,
+    },
+    state::*,
+    utils::*,
+};
+
+/// Optional Token-2022 extensions enabled when creating a mint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct MintExtensionArgs {
+    /// Route every transfer through the spl_hook transfer hook program
+    pub transfer_hook: bool,
+    /// Withhold a protocol fee on every transfer
+    pub transfer_fee: Option<TransferFeeArgs>,
+    /// Accrue interest at this rate (basis points per year) in UI amounts
+    pub interest_rate: Option<i16>,
+    /// Soulbound mint: tokens can be minted, burned and closed but never transferred
+    pub non_transferable: bool,
+    /// Delegate that can move or recover tokens from any holder of the mint
+    pub permanent_delegate: Option<Pubkey>,
+    /// New token accounts start frozen until thawed through the KYC workflow
+    pub default_frozen: bool,
+    /// Let holders keep encrypted balances and move tokens confidentially
+    pub confidential_transfer: Option<ConfidentialTransferArgs>,
+    /// The mint can become a token group (collection) via `create_group`
+    pub group_pointer: bool,
+    /// The mint can join a token group via `add_group_member`
+    pub group_member_pointer: bool,
+}
+
+/// Initial fee schedule for the TransferFeeConfig extension
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TransferFeeArgs {
+    /// Fee in basis points (e.g., 50 = 0.5%)
+    pub transfer_fee_basis_points: u16,
+    /// Upper bound on the fee charged for a single transfer
+    pub maximum_fee: u64,
+}
+
+impl MintExtensionArgs {
+    // Extensions that must be sized and initialized before the mint itself
+    pub fn extension_types(&self) -> Vec<ExtensionType> {
+        let mut extensions = vec![ExtensionType::MetadataPointer, ExtensionType::MintCloseAuthority];
+        if self.transfer_hook {
+            extensions.push(ExtensionType::TransferHook);
+        }
+        if self.transfer_fee.is_some() {
+            extensions.push(ExtensionType::TransferFeeConfig);
+        }
+        if self.interest_rate.is_some() {
+            extensions.push(ExtensionType::InterestBearingConfig);
+        }
+        if self.non_transferable {
+            extensions.push(ExtensionType::NonTransferable);
+        }
+        if self.permanent_delegate.is_some() {
+            extensions.push(ExtensionType::PermanentDelegate);
+        }
+        if self.default_frozen {
+            extensions.push(ExtensionType::DefaultAccountState);
+        }
+        if self.confidential_transfer.is_some() {
+            extensions.push(ExtensionType::ConfidentialTransferMint);
+        }
+        if self.group_pointer {
+            extensions.push(ExtensionType::GroupPointer);
+        }
+        if self.group_member_pointer {
+            extensions.push(ExtensionType::GroupMemberPointer);
+        }
+        extensions
+    }
+}
 
 /// SPL TOKEN FUNCTIONS
 // Create a new token mint with metadata
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<CreateMintAccount>,
-    _decimals: u8,
+    decimals: u8,
     name: String,
     symbol: String,
-    uri: String
+    uri: String,
+    extensions: MintExtensionArgs,
+    nonce: u64,
+    max_supply: Option<u64>
 ) -> Result<()> {
-    ctx.accounts.initialize_token_metadata(name, symbol, uri)?;
-    ctx.accounts.mint.reload()?;
+    if let Some(transfer_fee) = extensions.transfer_fee {
+        require!(
+            transfer_fee.transfer_fee_basis_points <= MAX_FEE_BASIS_POINTS,
+            TransferFeeError::InvalidFeeBasisPoints
+        );
+    }
+    // Fee-bearing confidential mints also need the confidential transfer fee extension
+    require!(
+        extensions.confidential_transfer.is_none() || extensions.transfer_fee.is_none(),
+        ConfidentialTransferError::TransferFeeNotSupported
+    );
+
+    ctx.accounts.initialize_mint(decimals, &extensions, nonce, ctx.bumps.mint)?;
+    ctx.accounts.create_mint_token_account()?;
+    ctx.accounts.initialize_token_metadata(name, symbol, uri, ctx.bumps.mint_authority)?;
+
+    // Create the approve account and meta list in the transfer hook program
+    if extensions.transfer_hook {
+        ctx.accounts.initialize_transfer_hook_accounts()?;
+    }
 
     // Update account lamports to minimum balance
     update_account_lamports_to_minimum_balance(
@@ -44,23 +193,71 @@ pub fn handler(
         ctx.accounts.system_program.to_account_info()
     )?;
 
+    // Record the supply cap (uncapped unless one is given)
+    ctx.accounts.mint_config.set_inner(MintConfig {
+        mint: ctx.accounts.mint.key(),
+        authority: ctx.accounts.authority.key(),
+        creator: ctx.accounts.authority.key(),
+        max_supply: max_supply.unwrap_or(u64::MAX),
+        bump: ctx.bumps.mint_config,
+    });
+
+    // Record the mint in the authority's registry
+    let registry_bump = ctx.bumps.mint_registry;
+    ctx.accounts.register_mint(registry_bump)?;
+
+    emit!(MintCreated {
+        mint: ctx.accounts.mint.key(),
+        authority: ctx.accounts.authority.key(),
+        receiver: ctx.accounts.receiver.key(),
+        decimals,
+        nonce,
+        max_supply: ctx.accounts.mint_config.max_supply,
+        transfer_hook: extensions.transfer_hook,
+    });
     Ok(())
 }
 
 // Mint new tokens to a specified account
 pub fn mint_spl_tokens(ctx: Context<MintTokens>, amount: u64) -> Result<()> {
+    require!(amount > 0, TokenError::ZeroAmount);
+    check_supply_cap(&ctx.accounts.mint_config, ctx.accounts.mint.supply, amount)?;
+    consume_minter_allowance(&mut ctx.accounts.minter, amount)?;
+
+    // The program PDA holds the mint authority and signs on the minter's behalf
+    let mint_key = ctx.accounts.mint.key();
+    let seeds: &[&[u8]] = &[MINT_AUTHORITY_SEED, mint_key.as_ref(), &[ctx.bumps.mint_authority]];
+    let signer_seeds = &[seeds];
+
     let cpi_accounts = MintTo {
         mint: ctx.accounts.mint.to_account_info(),
         to: ctx.accounts.to.to_account_info(),
-        authority: ctx.accounts.authority.to_account_info(),
+        authority: ctx.accounts.mint_authority.to_account_info(),
     };
-    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds
+    );
     mint_to(cpi_ctx, amount)?;
+
+    // Report the supply after the CPI so indexers can follow it without reading the mint
+    ctx.accounts.mint.reload()?;
+    emit!(TokensMinted {
+        mint: mint_key,
+        to: ctx.accounts.to.key(),
+        minter: ctx.accounts.authority.key(),
+        amount,
+        supply: ctx.accounts.mint.supply,
+    });
     Ok(())
 }
 
 // Burn tokens from a specified account
 pub fn burn_spl_tokens(ctx: Context<BurnTokens>, amount: u64) -> Result<()> {
+    require!(amount > 0, TokenError::ZeroAmount);
+    require!(ctx.accounts.from.amount >= amount, TokenError::InsufficientBalance);
+
     let cpi_accounts = Burn {
         mint: ctx.accounts.mint.to_account_info(),
         from: ctx.accounts.from.to_account_info(),
@@ -68,11 +265,22 @@ pub fn burn_spl_tokens(ctx: Context<BurnTokens>, amount: u64) -> Result<()> {
     };
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
     burn(cpi_ctx, amount)?;
+
+    ctx.accounts.mint.reload()?;
+    emit!(TokensBurned {
+        mint: ctx.accounts.mint.key(),
+        from: ctx.accounts.from.key(),
+        authority: ctx.accounts.authority.key(),
+        amount,
+        supply: ctx.accounts.mint.supply,
+    });
     Ok(())
 }
 
 // Close a token account and reclaim rent
 pub fn close_spl_token_account(ctx: Context<CloseTokenAccount>) -> Result<()> {
+    let lamports_refunded = ctx.accounts.account.get_lamports();
+
     let cpi_accounts = CloseAccount {
         account: ctx.accounts.account.to_account_info(),
         destination: ctx.accounts.destination.to_account_info(),
@@ -80,9 +288,86 @@ pub fn close_spl_token_account(ctx: Context<CloseTokenAccount>) -> Result<()> {
     };
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
     close_account(cpi_ctx)?;
+
+    emit!(AccountClosed {
+        account: ctx.accounts.account.key(),
+        mint: ctx.accounts.account.mint,
+        owner: ctx.accounts.account.owner,
+        destination: ctx.accounts.destination.key(),
+        lamports_refunded,
+    });
+    Ok(())
+}
+
+// Close an empty mint and every program PDA attached to it, refunding all rent to `destination`
+pub fn close_spl_mint<'info>(ctx: Context<'_, '_, 'info, 'info, CloseMint<'info>>) -> Result<()> {
+    require!(ctx.accounts.mint.supply == 0, TokenError::NonZeroSupply);
+
+    let mint_key = ctx.accounts.mint.key();
+    let destination = ctx.accounts.destination.to_account_info();
+    let starting_lamports = destination.get_lamports();
+
+    // Per-wallet PDAs (minter entries, KYC records) are passed as remaining accounts, along
+    // with the hook program's per-wallet PDAs (blocklist pages and velocity counters), which
+    // it validates itself
+    let mut hook_accounts = Vec::new();
+    for account in ctx.remaining_accounts.iter() {
+        if account.owner == &spl_hook::ID {
+            hook_accounts.push(account.clone());
+            continue;
+        }
+        require!(is_mint_companion(account, &mint_key), TokenError::InvalidCompanionAccount);
+        close_program_account(account, &destination)?;
+    }
+
+    // The hook program closes its own accounts of transfer-hook mints
+    if ctx.accounts.approve_account.owner == &spl_hook::ID {
+        ctx.accounts.close_transfer_hook_accounts(hook_accounts)?;
+    } else {
+        require!(hook_accounts.is_empty(), TokenError::InvalidCompanionAccount);
+    }
+
+    // The KYC config only exists for KYC-gated mints
+    let kyc_config = ctx.accounts.kyc_config.to_account_info();
+    if kyc_config.owner == &crate::ID {
+        close_program_account(&kyc_config, &destination)?;
+    }
+
+    ctx.accounts.unregister_mint()?;
+
+    // Token-2022 closes the mint itself through its close authority
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.mint.to_account_info(),
+        destination: destination.clone(),
+        authority: ctx.accounts.authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    close_account(cpi_ctx)?;
+
+    // The mint config is closed to `destination` on exit
+    let lamports_refunded = destination.get_lamports() - starting_lamports +
+        ctx.accounts.mint_config.get_lamports();
+
+    emit!(MintClosed {
+        mint: mint_key,
+        authority: ctx.accounts.authority.key(),
+        destination: destination.key(),
+        lamports_refunded,
+    });
     Ok(())
 }
 
+// Whether `account` is a minter entry or KYC record belonging to `mint`
+fn is_mint_companion<'info>(account: &'info AccountInfo<'info>, mint: &Pubkey) -> bool {
+    if let Ok(minter) = Account::<Minter>::try_from(account) {
+        return minter.mint == *mint;
+    }
+    if let Ok(record) = Account::<KycRecord>::try_from(account) {
+        return record.mint == *mint;
+    }
+    false
+}
+
 // Freeze a token account (prevent transfers)
 pub fn freeze_spl_token_account(ctx: Context<FreezeTokenAccount>) -> Result<()> {
     let cpi_accounts = FreezeAccount {
@@ -92,6 +377,12 @@ pub fn freeze_spl_token_account(ctx: Context<FreezeTokenAccount>) -> Result<()>
     };
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
     freeze_account(cpi_ctx)?;
+
+    emit!(AccountFrozen {
+        mint: ctx.accounts.mint.key(),
+        account: ctx.accounts.account.key(),
+        freeze_authority: ctx.accounts.freeze_authority.key(),
+    });
     Ok(())
 }
 
@@ -104,28 +395,288 @@ pub fn thaw_spl_token_account(ctx: Context<ThawTokenAccount>) -> Result<()> {
     };
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
     thaw_account(cpi_ctx)?;
+
+    emit!(AccountThawed {
+        mint: ctx.accounts.mint.key(),
+        account: ctx.accounts.account.key(),
+        freeze_authority: ctx.accounts.freeze_authority.key(),
+    });
     Ok(())
 }
 
-// Initialize token metadata for a mint
 impl<'info> CreateMintAccount<'info> {
-    fn initialize_token_metadata(&self, name: String, symbol: String, uri: String) -> Result<()> {
+    // Create the mint PDA, initialize the requested extensions and then the mint itself
+    fn initialize_mint(
+        &self,
+        decimals: u8,
+        extensions: &MintExtensionArgs,
+        nonce: u64,
+        bump: u8
+    ) -> Result<()> {
+        let extension_types = extensions.extension_types();
+        let space = find_mint_account_size(Some(&extension_types))?;
+        let authority_key = self.authority.key();
+        let nonce_bytes = nonce.to_le_bytes();
+        let seeds: &[&[u8]] = &[MINT_SEED, authority_key.as_ref(), &nonce_bytes, &[bump]];
+
+        create_account(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                CreateAccount {
+                    from: self.payer.to_account_info(),
+                    to: self.mint.to_account_info(),
+                },
+                &[seeds]
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &self.token_program.key()
+        )?;
+
+        let tp = self.token_program.to_account_info();
+        let mint = self.mint.to_account_info();
+
+        metadata_pointer_initialize(
+            CpiContext::new(tp.clone(), MetadataPointerInitialize {
+                token_program_id: tp.clone(),
+                mint: mint.clone(),
+            }),
+            Some(authority_key),
+            Some(mint.key())
+        )?;
+        mint_close_authority_initialize(
+            CpiContext::new(tp.clone(), MintCloseAuthorityInitialize {
+                token_program_id: tp.clone(),
+                mint: mint.clone(),
+            }),
+            Some(&authority_key)
+        )?;
+        if extensions.transfer_hook {
+            transfer_hook_initialize(
+                CpiContext::new(tp.clone(), TransferHookInitialize {
+                    token_program_id: tp.clone(),
+                    mint: mint.clone(),
+                }),
+                Some(authority_key),
+                Some(spl_hook::ID)
+            )?;
+        }
+        if let Some(transfer_fee) = extensions.transfer_fee {
+            transfer_fee_initialize(
+                CpiContext::new(tp.clone(), TransferFeeInitialize {
+                    token_program_id: tp.clone(),
+                    mint: mint.clone(),
+                }),
+                Some(&authority_key),
+                Some(&authority_key),
+                transfer_fee.transfer_fee_basis_points,
+                transfer_fee.maximum_fee
+            )?;
+        }
+        if let Some(rate) = extensions.interest_rate {
+            interest_bearing_mint_initialize(
+                CpiContext::new(tp.clone(), InterestBearingMintInitialize {
+                    token_program_id: tp.clone(),
+                    mint: mint.clone(),
+                }),
+                Some(authority_key),
+                rate
+            )?;
+        }
+        if extensions.non_transferable {
+            non_transferable_mint_initialize(
+                CpiContext::new(tp.clone(), NonTransferableMintInitialize {
+                    token_program_id: tp.clone(),
+                    mint: mint.clone(),
+                })
+            )?;
+        }
+        if let Some(delegate) = extensions.permanent_delegate {
+            permanent_delegate_initialize(
+                CpiContext::new(tp.clone(), PermanentDelegateInitialize {
+                    token_program_id: tp.clone(),
+                    mint: mint.clone(),
+                }),
+                &delegate
+            )?;
+        }
+        if extensions.default_frozen {
+            default_account_state_initialize(
+                CpiContext::new(tp.clone(), DefaultAccountStateInitialize {
+                    token_program_id: tp.clone(),
+                    mint: mint.clone(),
+                }),
+                &AccountState::Frozen
+            )?;
+        }
+        if let Some(confidential) = extensions.confidential_transfer {
+            let ix = confidential_transfer_initialize(
+                tp.key,
+                mint.key,
+                Some(authority_key),
+                confidential.auto_approve_new_accounts,
+                confidential.auditor_elgamal_pubkey.map(ElGamalPubkey)
+            )?;
+            invoke(&ix, &[tp.clone(), mint.clone()])?;
+        }
+        if extensions.group_pointer {
+            group_pointer_initialize(
+                CpiContext::new(tp.clone(), GroupPointerInitialize {
+                    token_program_id: tp.clone(),
+                    mint: mint.clone(),
+                }),
+                Some(authority_key),
+                Some(mint.key())
+            )?;
+        }
+        if extensions.group_member_pointer {
+            group_member_pointer_initialize(
+                CpiContext::new(tp.clone(), GroupMemberPointerInitialize {
+                    token_program_id: tp.clone(),
+                    mint: mint.clone(),
+                }),
+                Some(authority_key),
+                Some(mint.key())
+            )?;
+        }
+
+        // Minting goes through the minter registry, so the program PDA holds the mint authority
+        initialize_mint2(
+            CpiContext::new(tp, InitializeMint2 { mint }),
+            decimals,
+            &self.mint_authority.key(),
+            Some(&authority_key)
+        )?;
+        Ok(())
+    }
+
+    // Create the receiver's associated token account for the new mint
+    // (Token-2022 ATAs always carry ImmutableOwner, which soulbound mints require)
+    fn create_mint_token_account(&self) -> Result<()> {
+        let cpi_accounts = Create {
+            payer: self.payer.to_account_info(),
+            associated_token: self.mint_token_account.to_account_info(),
+            authority: self.receiver.to_account_info(),
+            mint: self.mint.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.associated_token_program.to_account_info(), cpi_accounts);
+        create_idempotent(cpi_ctx)?;
+        Ok(())
+    }
+
+    // Initialize token metadata for a mint; the program PDA holds the mint authority, so it signs
+    fn initialize_token_metadata(
+        &self,
+        name: String,
+        symbol: String,
+        uri: String,
+        mint_authority_bump: u8
+    ) -> Result<()> {
+        let mint_key = self.mint.key();
+        let seeds: &[&[u8]] = &[MINT_AUTHORITY_SEED, mint_key.as_ref(), &[mint_authority_bump]];
+        let signer_seeds = &[seeds];
+
         let cpi_accounts = TokenMetadataInitialize {
             token_program_id: self.token_program.to_account_info(),
             mint: self.mint.to_account_info(),
             metadata: self.mint.to_account_info(),
-            mint_authority: self.authority.to_account_info(),
+            mint_authority: self.mint_authority.to_account_info(),
             update_authority: self.authority.to_account_info(),
         };
-        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds
+        );
         token_metadata_initialize(cpi_ctx, name, symbol, uri)?;
         Ok(())
     }
+
+    // Have the hook program create the approve account and the meta list pointing at it
+    fn initialize_transfer_hook_accounts(&self) -> Result<()> {
+        let cpi_accounts = InitializeHook {
+            payer: self.payer.to_account_info(),
+            authority: self.authority.to_account_info(),
+            mint: self.mint.to_account_info(),
+            approve_account: self.approve_account.to_account_info(),
+            extra_metas_account: self.extra_metas_account.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.hook_program.to_account_info(), cpi_accounts);
+        spl_hook::cpi::initialize_hook(cpi_ctx)
+    }
+
+    // Append the new mint to the authority's registry, growing the account by one entry
+    fn register_mint(&mut self, bump: u8) -> Result<()> {
+        let registry = &mut self.mint_registry;
+        if registry.authority == Pubkey::default() {
+            registry.authority = self.authority.key();
+            registry.bump = bump;
+        }
+        registry.mints.push(self.mint.key());
+
+        let registry_info = registry.to_account_info();
+        registry_info.realloc(MintRegistry::space(registry.mints.len()), false)?;
+        update_account_lamports_to_minimum_balance(
+            registry_info,
+            self.payer.to_account_info(),
+            self.system_program.to_account_info()
+        )
+    }
+}
+
+impl<'info> CloseMint<'info> {
+    // Have the hook program close the approve account, meta list, policy and velocity
+    // accounts, refunding `destination`
+    fn close_transfer_hook_accounts(&self, hook_accounts: Vec<AccountInfo<'info>>) -> Result<()> {
+        let cpi_accounts = CloseHookAccounts {
+            authority: self.authority.to_account_info(),
+            destination: self.destination.to_account_info(),
+            mint: self.mint.to_account_info(),
+            approve_account: self.approve_account.to_account_info(),
+            extra_metas_account: self.extra_metas_account.to_account_info(),
+            mint_policy: self.mint_policy.to_account_info(),
+            velocity_config: self.velocity_config.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            self.hook_program.to_account_info(),
+            cpi_accounts
+        ).with_remaining_accounts(hook_accounts);
+        spl_hook::cpi::close_hook(cpi_ctx)
+    }
+
+    // Drop the mint from the authority's registry and refund the rent freed by shrinking it
+    fn unregister_mint(&mut self) -> Result<()> {
+        let mint_key = self.mint.key();
+        let registry = &mut self.mint_registry;
+        registry.mints.retain(|mint| mint != &mint_key);
+
+        let registry_info = registry.to_account_info();
+        registry_info.realloc(MintRegistry::space(registry.mints.len()), false)?;
+        let excess = registry_info
+            .get_lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(registry_info.data_len()));
+        if excess > 0 {
+            registry_info.sub_lamports(excess)?;
+            self.destination.add_lamports(excess)?;
+        }
+        Ok(())
+    }
 }
 
 /// SPL TOKEN ACCOUNTS FUNCTIONS
 #[derive(Accounts)]
-#[instruction(decimals: u8, name: String, symbol: String, uri: String)]
+#[instruction(
+    decimals: u8,
+    name: String,
+    symbol: String,
+    uri: String,
+    extensions: MintExtensionArgs,
+    nonce: u64,
+    max_supply: Option<u64>
+)]
 pub struct CreateMintAccount<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -135,66 +686,89 @@ pub struct CreateMintAccount<'info> {
     #[account()]
     /// CHECK: can be any account
     pub receiver: UncheckedAccount<'info>,
+    /// CHECK: created and initialized with its extensions in the handler
+    #[account(mut, seeds = [MINT_SEED, authority.key().as_ref(), &nonce.to_le_bytes()], bump)]
+    pub mint: UncheckedAccount<'info>,
+    /// Enumerates every mint created by the authority
     #[account(
-        init,
-        seeds = [b"mint", authority.key().as_ref()],
-        bump,
+        init_if_needed,
         payer = payer,
-        mint::token_program = token_program,
-        mint::decimals = decimals,
-        mint::authority = authority,
-        mint::freeze_authority = authority,
-        extensions::metadata_pointer::authority = authority,
-        extensions::metadata_pointer::metadata_address = mint,
-        extensions::close_authority::authority = authority
+        space = MintRegistry::space(0),
+        seeds = [MINT_REGISTRY_SEED, authority.key().as_ref()],
+        bump
     )]
-    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_registry: Box<Account<'info, MintRegistry>>,
+    /// Per-mint config holding the supply cap
     #[account(
-        init_if_needed,
+        init,
         payer = payer,
-        associated_token::token_program = token_program,
-        associated_token::mint = mint,
-        associated_token::authority = receiver
+        space = 8 + size_of::<MintConfig>(),
+        seeds = [MINT_CONFIG_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+    /// CHECK: program PDA set as the mint authority
+    #[account(seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    /// CHECK: receiver's associated token account, created in the handler
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &receiver.key(),
+            &mint.key(),
+            &token_program.key()
+        )
     )]
-    pub mint_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub mint_token_account: UncheckedAccount<'info>,
 
-    /// CHECK: This account's data is a buffer of TLV data
+    /// CHECK: hook program PDA, only created when the transfer hook is enabled
     #[account(
-        init_if_needed,
-        space = get_meta_list_size(None),
+        mut,
+        seeds = [APPROVE_ACCOUNT_SEED, mint.key().as_ref()],
+        bump,
+        seeds::program = hook_program.key()
+    )]
+    pub approve_account: UncheckedAccount<'info>,
+    /// CHECK: hook program PDA, only created when the transfer hook is enabled
+    #[account(
+        mut,
         seeds = [META_LIST_ACCOUNT_SEED, mint.key().as_ref()],
         bump,
-        payer = payer
+        seeds::program = hook_program.key()
     )]
     pub extra_metas_account: UncheckedAccount<'info>,
+    pub hook_program: Program<'info, SplHook>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+    /// Mints are created with Token-2022 extensions, so this cannot be the classic program
     pub token_program: Program<'info, Token2022>,
 }
 
-#[derive(Accounts)]
-pub struct CheckMintExtensionConstraints<'info> {
-    #[account(mut)]
-    /// CHECK: can be any account
-    pub authority: Signer<'info>,
-    #[account(
-        extensions::metadata_pointer::authority = authority,
-        extensions::metadata_pointer::metadata_address = mint,
-        extensions::close_authority::authority = authority
-    )]
-    pub mint: Box<InterfaceAccount<'info, Mint>>,
-}
-
 #[derive(Accounts)]
 pub struct MintTokens<'info> {
     #[account(mut)]
     pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// Supply cap for the mint
+    #[account(seeds = [MINT_CONFIG_SEED, mint.key().as_ref()], bump = mint_config.bump)]
+    pub mint_config: Box<Account<'info, MintConfig>>,
     /// The token account to receive newly minted tokens
-    #[account(mut)]
+    #[account(mut, constraint = to.mint == mint.key() @ TokenError::MintMismatch)]
     pub to: Box<InterfaceAccount<'info, TokenAccount>>,
-    /// Must match the mint's authority
+    /// A registered minter for this mint
     pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token2022>,
+    /// Registry entry carrying the minter's allowance and rate limit
+    #[account(
+        mut,
+        seeds = [MINTER_SEED, mint.key().as_ref(), authority.key().as_ref()],
+        bump = minter.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub minter: Box<Account<'info, Minter>>,
+    /// CHECK: program PDA holding the mint authority
+    #[account(seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
@@ -202,46 +776,218 @@ pub struct BurnTokens<'info> {
     #[account(mut)]
     pub mint: Box<InterfaceAccount<'info, Mint>>,
     /// The token account to burn from (must have sufficient balance)
-    #[account(mut)]
+    #[account(mut, constraint = from.mint == mint.key() @ TokenError::MintMismatch)]
     pub from: Box<InterfaceAccount<'info, TokenAccount>>,
-    /// Must match the mint's authority (or the account's delegate)
+    /// Owner or delegate of `from`
+    #[account(
+        constraint = from.owner == authority.key() ||
+        from.delegate == COption::Some(authority.key()) @ TokenError::AuthorityMismatch
+    )]
     pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token2022>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct CloseTokenAccount<'info> {
-    /// The token account to close (must be empty)
-    #[account(mut)]
+    /// The token account to close (must be empty unless it holds wrapped SOL)
+    #[account(
+        mut,
+        constraint = account.amount == 0 || account.is_native() @ TokenError::NonEmptyAccount
+    )]
     pub account: Box<InterfaceAccount<'info, TokenAccount>>,
     /// Destination of the reclaimed SOL
     #[account(mut)]
     pub destination: Signer<'info>,
-    /// The close-authority of `account`
+    /// The close-authority of `account` (its owner when no close authority is set)
+    #[account(
+        constraint = account.close_authority.unwrap_or(account.owner) == authority.key() @ TokenError::AuthorityMismatch
+    )]
     pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMint<'info> {
+    /// Close authority of the mint (whoever holds it after any rotation)
+    pub authority: Signer<'info>,
+    /// CHECK: any account can receive the refunded rent
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    /// The mint to close (supply must be zero)
+    #[account(mut, extensions::close_authority::authority = authority)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [MINT_CONFIG_SEED, mint.key().as_ref()],
+        bump = mint_config.bump,
+        has_one = mint,
+        close = destination
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+    /// Registry of the wallet that created the mint
+    #[account(
+        mut,
+        seeds = [MINT_REGISTRY_SEED, mint_config.creator.as_ref()],
+        bump = mint_registry.bump
+    )]
+    pub mint_registry: Box<Account<'info, MintRegistry>>,
+    /// CHECK: hook program PDA, only initialized for transfer-hook mints and closed through
+    /// the hook program when present
+    #[account(
+        mut,
+        seeds = [META_LIST_ACCOUNT_SEED, mint.key().as_ref()],
+        bump,
+        seeds::program = hook_program.key()
+    )]
+    pub extra_metas_account: UncheckedAccount<'info>,
+    /// CHECK: hook program PDA, only initialized for transfer-hook mints
+    #[account(
+        mut,
+        seeds = [APPROVE_ACCOUNT_SEED, mint.key().as_ref()],
+        bump,
+        seeds::program = hook_program.key()
+    )]
+    pub approve_account: UncheckedAccount<'info>,
+    /// CHECK: hook program PDA, only initialized once a hooked mint has a transfer policy
+    #[account(
+        mut,
+        seeds = [MINT_POLICY_SEED, mint.key().as_ref()],
+        bump,
+        seeds::program = hook_program.key()
+    )]
+    pub mint_policy: UncheckedAccount<'info>,
+    /// CHECK: hook program PDA, only initialized once a hooked mint has velocity limits
+    #[account(
+        mut,
+        seeds = [VELOCITY_CONFIG_SEED, mint.key().as_ref()],
+        bump,
+        seeds::program = hook_program.key()
+    )]
+    pub velocity_config: UncheckedAccount<'info>,
+    /// CHECK: only initialized for KYC-gated mints, closed in the handler when present
+    #[account(mut, seeds = [KYC_CONFIG_SEED, mint.key().as_ref()], bump)]
+    pub kyc_config: UncheckedAccount<'info>,
+    /// Only Token-2022 mints carry a close authority
     pub token_program: Program<'info, Token2022>,
+    pub hook_program: Program<'info, SplHook>,
 }
 
 #[derive(Accounts)]
 pub struct FreezeTokenAccount<'info> {
     /// The token account to freeze (must be initialized)
-    #[account(mut)]
+    #[account(mut, constraint = account.mint == mint.key() @ TokenError::MintMismatch)]
     pub account: Box<InterfaceAccount<'info, TokenAccount>>,
     /// The mint under which this account exists
     pub mint: Box<InterfaceAccount<'info, Mint>>,
     /// Must match the mint's freeze_authority
+    #[account(
+        constraint = mint.freeze_authority == COption::Some(freeze_authority.key()) @ TokenError::AuthorityMismatch
+    )]
     pub freeze_authority: Signer<'info>,
-    pub token_program: Program<'info, Token2022>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct ThawTokenAccount<'info> {
     /// The token account to thaw
-    #[account(mut)]
+    #[account(mut, constraint = account.mint == mint.key() @ TokenError::MintMismatch)]
     pub account: Box<InterfaceAccount<'info, TokenAccount>>,
     /// The same mint used when freezing
     pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: only initialized for KYC-gated mints, which thaw through thaw_kyc_token_account
+    #[account(
+        seeds = [KYC_CONFIG_SEED, mint.key().as_ref()],
+        bump,
+        constraint = kyc_config.owner != &crate::ID @ TokenError::KycGatedMint
+    )]
+    pub kyc_config: UncheckedAccount<'info>,
     /// Must match the mint's freeze_authority
+    #[account(
+        constraint = mint.freeze_authority == COption::Some(freeze_authority.key()) @ TokenError::AuthorityMismatch
+    )]
     pub freeze_authority: Signer<'info>,
-    pub token_program: Program<'info, Token2022>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct MintCreated {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub receiver: Pubkey,
+    pub decimals: u8,
+    pub nonce: u64,
+    /// u64::MAX when the mint is uncapped
+    pub max_supply: u64,
+    pub transfer_hook: bool,
+}
+
+#[event]
+pub struct TokensMinted {
+    pub mint: Pubkey,
+    pub to: Pubkey,
+    pub minter: Pubkey,
+    pub amount: u64,
+    /// Mint supply after the mint
+    pub supply: u64,
+}
+
+#[event]
+pub struct TokensBurned {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    /// Mint supply after the burn
+    pub supply: u64,
+}
+
+#[event]
+pub struct AccountFrozen {
+    pub mint: Pubkey,
+    pub account: Pubkey,
+    pub freeze_authority: Pubkey,
+}
+
+#[event]
+pub struct AccountThawed {
+    pub mint: Pubkey,
+    pub account: Pubkey,
+    pub freeze_authority: Pubkey,
+}
+
+#[event]
+pub struct AccountClosed {
+    pub account: Pubkey,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub destination: Pubkey,
+    pub lamports_refunded: u64,
+}
+
+#[event]
+pub struct MintClosed {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub lamports_refunded: u64,
+}
+
+#[error_code]
+pub enum TokenError {
+    #[msg("mint supply must be zero before it can be closed")]
+    NonZeroSupply,
+    #[msg("remaining account is not a minter or KYC record of this mint")]
+    InvalidCompanionAccount,
+    #[msg("amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("token account belongs to a different mint")]
+    MintMismatch,
+    #[msg("signer is not the authority for this account")]
+    AuthorityMismatch,
+    #[msg("token account balance is too low")]
+    InsufficientBalance,
+    #[msg("token account must be empty before it can be closed")]
+    NonEmptyAccount,
+    #[msg("KYC-gated mints are thawed through thaw_kyc_token_account")]
+    KycGatedMint,
 }
@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022_extensions::{
+        harvest_withheld_tokens_to_mint,
+        transfer_fee_set,
+        withdraw_withheld_tokens_from_mint,
+        HarvestWithheldTokensToMint,
+        TransferFeeSetTransferFee,
+        WithdrawWithheldTokensFromMint,
+    },
+    token_interface::{
+        spl_token_2022::extension::transfer_fee::{ TransferFeeConfig, MAX_FEE_BASIS_POINTS },
+        Mint,
+        Token2022,
+        TokenAccount,
+    },
+};
+
+use crate::utils::*;
+
+/// TRANSFER FEE FUNCTIONS
+// Move fees withheld in token accounts (passed as remaining accounts) into the mint
+pub fn harvest_withheld_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, HarvestWithheldFees<'info>>
+) -> Result<()> {
+    require!(!ctx.remaining_accounts.is_empty(), TransferFeeError::NoSourceAccounts);
+
+    let cpi_accounts = HarvestWithheldTokensToMint {
+        token_program_id: ctx.accounts.token_program.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    harvest_withheld_tokens_to_mint(cpi_ctx, ctx.remaining_accounts.to_vec())?;
+
+    let fee_config = get_mint_extension_data::<TransferFeeConfig>(
+        &mut ctx.accounts.mint.to_account_info()
+    )?;
+    emit!(FeesHarvested {
+        mint: ctx.accounts.mint.key(),
+        sources: ctx.remaining_accounts.len() as u32,
+        withheld_in_mint: u64::from(fee_config.withheld_amount),
+    });
+    Ok(())
+}
+
+// Withdraw fees withheld in the mint to a treasury token account
+pub fn withdraw_withheld_fees(ctx: Context<WithdrawWithheldFees>) -> Result<()> {
+    // Read the withheld amount before the token program zeroes it
+    let fee_config = get_mint_extension_data::<TransferFeeConfig>(
+        &mut ctx.accounts.mint.to_account_info()
+    )?;
+    let amount = u64::from(fee_config.withheld_amount);
+
+    let cpi_accounts = WithdrawWithheldTokensFromMint {
+        token_program_id: ctx.accounts.token_program.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        destination: ctx.accounts.treasury.to_account_info(),
+        authority: ctx.accounts.withdraw_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    withdraw_withheld_tokens_from_mint(cpi_ctx)?;
+
+    emit!(FeesWithdrawn {
+        mint: ctx.accounts.mint.key(),
+        treasury: ctx.accounts.treasury.key(),
+        amount,
+    });
+    Ok(())
+}
+
+// Update the mint's transfer fee schedule (takes effect after two epochs)
+pub fn update_transfer_fee(
+    ctx: Context<UpdateTransferFee>,
+    transfer_fee_basis_points: u16,
+    maximum_fee: u64
+) -> Result<()> {
+    require!(
+        transfer_fee_basis_points <= MAX_FEE_BASIS_POINTS,
+        TransferFeeError::InvalidFeeBasisPoints
+    );
+
+    let cpi_accounts = TransferFeeSetTransferFee {
+        token_program_id: ctx.accounts.token_program.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.fee_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    transfer_fee_set(cpi_ctx, transfer_fee_basis_points, maximum_fee)?;
+
+    let fee_config = get_mint_extension_data::<TransferFeeConfig>(
+        &mut ctx.accounts.mint.to_account_info()
+    )?;
+    emit!(TransferFeeUpdated {
+        mint: ctx.accounts.mint.key(),
+        transfer_fee_basis_points,
+        maximum_fee,
+        effective_epoch: u64::from(fee_config.newer_transfer_fee.epoch),
+    });
+    Ok(())
+}
+
+/// TRANSFER FEE ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct HarvestWithheldFees<'info> {
+    /// Mint that collects the harvested fees
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawWithheldFees<'info> {
+    /// Must match the mint's withdraw-withheld authority
+    pub withdraw_authority: Signer<'info>,
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    /// Token account that receives the withheld fees
+    #[account(mut, token::mint = mint)]
+    pub treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTransferFee<'info> {
+    /// Must match the mint's transfer-fee-config authority
+    pub fee_authority: Signer<'info>,
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event]
+pub struct FeesHarvested {
+    pub mint: Pubkey,
+    pub sources: u32,
+    pub withheld_in_mint: u64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub mint: Pubkey,
+    pub treasury: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TransferFeeUpdated {
+    pub mint: Pubkey,
+    pub transfer_fee_basis_points: u16,
+    pub maximum_fee: u64,
+    pub effective_epoch: u64,
+}
+
+#[error_code]
+pub enum TransferFeeError {
+    #[msg("transfer fee basis points exceed 10000")]
+    InvalidFeeBasisPoints,
+    #[msg("no token accounts passed to harvest from")]
+    NoSourceAccounts,
+}
@@ -0,0 +1,362 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{ Mint, TokenAccount, TokenInterface, TransferChecked },
+};
+use std::mem::size_of;
+
+use crate::{ state::*, utils::* };
+
+/// Timing of a vesting schedule
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct VestingScheduleArgs {
+    /// Timestamp vesting is measured from
+    pub start_time: i64,
+    /// Nothing is releasable before this timestamp
+    pub cliff_time: i64,
+    /// Everything is releasable from this timestamp
+    pub end_time: i64,
+    /// Tokens unlock in steps of this many seconds
+    pub period: i64,
+    /// Whether the grantor may revoke the unvested remainder
+    pub revocable: bool,
+}
+
+/// VESTING FUNCTIONS
+// Lock tokens in a PDA vault under a cliff + linear release schedule
+pub fn create_vesting_schedule<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateVesting<'info>>,
+    id: u64,
+    amount: u64,
+    schedule: VestingScheduleArgs
+) -> Result<()> {
+    require!(amount > 0, VestingError::ZeroAmount);
+    require!(
+        schedule.start_time <= schedule.cliff_time &&
+            schedule.cliff_time <= schedule.end_time &&
+            schedule.start_time < schedule.end_time,
+        VestingError::InvalidSchedule
+    );
+    require!(
+        schedule.period > 0 && schedule.period <= schedule.end_time - schedule.start_time,
+        VestingError::InvalidPeriod
+    );
+    require!(
+        !is_non_transferable_mint(&ctx.accounts.mint.to_account_info())?,
+        VestingError::NonTransferableMint
+    );
+
+    // Transfer the allocation from the grantor into the vault
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.grantor_token_account.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.grantor.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts
+    ).with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    transfer_checked_with_hook(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    // Vest what actually arrived (transfer-fee mints withhold part of the transfer)
+    ctx.accounts.vault.reload()?;
+    let total_amount = ctx.accounts.vault.amount;
+
+    let vesting = &mut ctx.accounts.vesting;
+    vesting.grantor = ctx.accounts.grantor.key();
+    vesting.beneficiary = ctx.accounts.beneficiary.key();
+    vesting.mint = ctx.accounts.mint.key();
+    vesting.vault = ctx.accounts.vault.key();
+    vesting.id = id;
+    vesting.total_amount = total_amount;
+    vesting.released_amount = 0;
+    vesting.start_time = schedule.start_time;
+    vesting.cliff_time = schedule.cliff_time;
+    vesting.end_time = schedule.end_time;
+    vesting.period = schedule.period;
+    vesting.revocable = schedule.revocable;
+    vesting.revoked = false;
+    vesting.bump = ctx.bumps.vesting;
+
+    emit!(VestingCreated {
+        vesting: vesting.key(),
+        grantor: vesting.grantor,
+        beneficiary: vesting.beneficiary,
+        mint: vesting.mint,
+        total_amount,
+        start_time: schedule.start_time,
+        cliff_time: schedule.cliff_time,
+        end_time: schedule.end_time,
+    });
+    Ok(())
+}
+
+// Pay the beneficiary everything unlocked so far
+pub fn release_vested_tokens<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ReleaseVested<'info>>
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vesting = &ctx.accounts.vesting;
+    let releasable = vesting
+        .vested_amount(now)?
+        .checked_sub(vesting.released_amount)
+        .ok_or(VestingError::MathOverflow)?;
+    require!(releasable > 0, VestingError::NothingToRelease);
+
+    transfer_from_vault(
+        &ctx.accounts.vesting,
+        &ctx.accounts.vault,
+        ctx.accounts.beneficiary_token_account.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        releasable
+    )?;
+
+    let vesting = &mut ctx.accounts.vesting;
+    vesting.released_amount = vesting.released_amount
+        .checked_add(releasable)
+        .ok_or(VestingError::MathOverflow)?;
+
+    emit!(VestingReleased {
+        vesting: vesting.key(),
+        beneficiary: vesting.beneficiary,
+        amount: releasable,
+        released_amount: vesting.released_amount,
+        time: now,
+    });
+    Ok(())
+}
+
+// Return the unvested remainder to the grantor; vested tokens stay releasable
+pub fn revoke_vesting_schedule<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RevokeVesting<'info>>
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vesting = &ctx.accounts.vesting;
+    require!(vesting.revocable, VestingError::NotRevocable);
+    require!(!vesting.revoked, VestingError::AlreadyRevoked);
+
+    let vested = vesting.vested_amount(now)?;
+    let unvested = vesting.total_amount
+        .checked_sub(vested)
+        .ok_or(VestingError::MathOverflow)?;
+    if unvested > 0 {
+        transfer_from_vault(
+            &ctx.accounts.vesting,
+            &ctx.accounts.vault,
+            ctx.accounts.grantor_token_account.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+            unvested
+        )?;
+    }
+
+    // Freeze the schedule at what had vested
+    let vesting = &mut ctx.accounts.vesting;
+    vesting.total_amount = vested;
+    vesting.revoked = true;
+
+    emit!(VestingRevoked {
+        vesting: vesting.key(),
+        grantor: vesting.grantor,
+        returned_amount: unvested,
+        vested_amount: vested,
+        time: now,
+    });
+    Ok(())
+}
+
+impl VestingSchedule {
+    // Amount unlocked at `now`: nothing before the cliff, then linear in whole periods until the end
+    pub fn vested_amount(&self, now: i64) -> Result<u64> {
+        if self.revoked || now >= self.end_time {
+            return Ok(self.total_amount);
+        }
+        if now < self.cliff_time {
+            return Ok(0);
+        }
+        let elapsed = now - self.start_time;
+        let unlocked = (elapsed - (elapsed % self.period)) as u128;
+        let duration = (self.end_time - self.start_time) as u128;
+        let vested = (self.total_amount as u128)
+            .checked_mul(unlocked)
+            .ok_or(VestingError::MathOverflow)?
+            .checked_div(duration)
+            .ok_or(VestingError::MathOverflow)?;
+        Ok(vested as u64)
+    }
+}
+
+// Transfer out of a vesting vault, signed by the vesting PDA (`hook_accounts` are the transfer
+// hook's extra accounts for hooked mints)
+fn transfer_from_vault<'info>(
+    vesting: &Account<'info, VestingSchedule>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    hook_accounts: &[AccountInfo<'info>],
+    amount: u64
+) -> Result<()> {
+    let id_bytes = vesting.id.to_le_bytes();
+    let seeds: &[&[u8]] = &[
+        VESTING_SEED,
+        vesting.mint.as_ref(),
+        vesting.beneficiary.as_ref(),
+        &id_bytes,
+        &[vesting.bump],
+    ];
+    let signer_seeds = &[seeds];
+
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        to,
+        authority: vesting.to_account_info(),
+        mint: mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds
+    ).with_remaining_accounts(hook_accounts.to_vec());
+    transfer_checked_with_hook(cpi_ctx, amount, mint.decimals)
+}
+
+/// VESTING ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateVesting<'info> {
+    #[account(mut)]
+    pub grantor: Signer<'info>,
+    /// CHECK: wallet that receives the vested tokens
+    pub beneficiary: UncheckedAccount<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, token::mint = mint, token::authority = grantor)]
+    pub grantor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = grantor,
+        space = 8 + size_of::<VestingSchedule>(),
+        seeds = [VESTING_SEED, mint.key().as_ref(), beneficiary.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub vesting: Box<Account<'info, VestingSchedule>>,
+    /// The associated token account owned by the vesting PDA that holds locked tokens
+    #[account(
+        init,
+        associated_token::mint = mint,
+        associated_token::authority = vesting,
+        associated_token::token_program = token_program,
+        payer = grantor
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseVested<'info> {
+    pub beneficiary: Signer<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            VESTING_SEED,
+            mint.key().as_ref(),
+            beneficiary.key().as_ref(),
+            &vesting.id.to_le_bytes(),
+        ],
+        bump = vesting.bump,
+        has_one = beneficiary,
+        has_one = mint,
+        has_one = vault
+    )]
+    pub vesting: Box<Account<'info, VestingSchedule>>,
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = mint, token::authority = beneficiary)]
+    pub beneficiary_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    pub grantor: Signer<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            VESTING_SEED,
+            mint.key().as_ref(),
+            vesting.beneficiary.as_ref(),
+            &vesting.id.to_le_bytes(),
+        ],
+        bump = vesting.bump,
+        has_one = grantor,
+        has_one = mint,
+        has_one = vault
+    )]
+    pub vesting: Box<Account<'info, VestingSchedule>>,
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = mint, token::authority = grantor)]
+    pub grantor_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct VestingCreated {
+    pub vesting: Pubkey,
+    pub grantor: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub total_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct VestingReleased {
+    pub vesting: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub released_amount: u64,
+    pub time: i64,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub vesting: Pubkey,
+    pub grantor: Pubkey,
+    pub returned_amount: u64,
+    pub vested_amount: u64,
+    pub time: i64,
+}
+
+#[error_code]
+pub enum VestingError {
+    #[msg("amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("schedule must satisfy start <= cliff <= end and start < end")]
+    InvalidSchedule,
+    #[msg("release period must be positive and no longer than the schedule")]
+    InvalidPeriod,
+    #[msg("non-transferable mints cannot be vested")]
+    NonTransferableMint,
+    #[msg("no vested tokens to release")]
+    NothingToRelease,
+    #[msg("vesting schedule is not revocable")]
+    NotRevocable,
+    #[msg("vesting schedule has already been revoked")]
+    AlreadyRevoked,
+    #[msg("math overflow")]
+    MathOverflow,
+}
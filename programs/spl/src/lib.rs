@@ -19,28 +19,48 @@ pub mod spl {
     // TOKEN MANAGEMENT FUNCTIONS
 
     // Create new SPL Token-2022 with metadata
+    #[allow(clippy::too_many_arguments)]
     pub fn create_mint_account(
         ctx: Context<CreateMintAccount>,
         decimals: u8,
         name: String,
         symbol: String,
-        uri: String
+        uri: String,
+        extensions: MintExtensionArgs,
+        nonce: u64,
+        max_supply: Option<u64>
     ) -> Result<()> {
-        instructions::handler(ctx, decimals, name, symbol, uri)
+        instructions::handler(ctx, decimals, name, symbol, uri, extensions, nonce, max_supply)
     }
 
-    // Validate mint extension constraints
+    // Report every extension on a mint (authorities, fees, hook program, metadata) via return data
     pub fn check_mint_extensions_constraints(
-        _ctx: Context<CheckMintExtensionConstraints>
-    ) -> Result<()> {
-        Ok(())
+        ctx: Context<CheckMintExtensionConstraints>
+    ) -> Result<MintExtensionReport> {
+        instructions::inspect_mint_extensions(ctx)
     }
 
-    // Mint new tokens to specified account
+    // Mint new tokens to specified account (registered minters only)
     pub fn mint_tokens(ctx: Context<MintTokens>, amount: u64) -> Result<()> {
         instructions::mint_spl_tokens(ctx, amount)
     }
 
+    // Mint to many token accounts in one instruction (registered minters only)
+    pub fn batch_mint_tokens<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchMintTokens<'info>>,
+        amounts: Vec<u64>
+    ) -> Result<()> {
+        instructions::batch_mint_spl_tokens(ctx, amounts)
+    }
+
+    // Transfer from one token account to many in one instruction
+    pub fn batch_transfer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchTransfer<'info>>,
+        amounts: Vec<u64>
+    ) -> Result<()> {
+        instructions::batch_transfer_spl_tokens(ctx, amounts)
+    }
+
     // Burn tokens from specified account
     pub fn burn_tokens(ctx: Context<BurnTokens>, amount: u64) -> Result<()> {
         instructions::burn_spl_tokens(ctx, amount)
@@ -51,6 +71,11 @@ pub mod spl {
         instructions::close_spl_token_account(ctx)
     }
 
+    // Close an empty mint and its program PDAs, refunding all rent
+    pub fn close_mint<'info>(ctx: Context<'_, '_, 'info, 'info, CloseMint<'info>>) -> Result<()> {
+        instructions::close_spl_mint(ctx)
+    }
+
     // Freeze token account (prevent transfers)
     pub fn freeze_token_account(ctx: Context<FreezeTokenAccount>) -> Result<()> {
         instructions::freeze_spl_token_account(ctx)
@@ -61,6 +86,391 @@ pub mod spl {
         instructions::thaw_spl_token_account(ctx)
     }
 
+    // TOKEN ACCOUNT EXTENSION FUNCTIONS
+
+    // Grow a token account to fit extra extensions (owner pays the rent)
+    pub fn reallocate_token_account(
+        ctx: Context<ManageTokenAccount>,
+        extensions: Vec<AccountExtension>
+    ) -> Result<()> {
+        instructions::reallocate_spl_token_account(ctx, extensions)
+    }
+
+    // Require memos on incoming transfers to a token account
+    pub fn enable_memo_transfer(ctx: Context<ManageTokenAccount>) -> Result<()> {
+        instructions::enable_required_memo_transfers(ctx)
+    }
+
+    // Stop requiring memos on incoming transfers to a token account
+    pub fn disable_memo_transfer(ctx: Context<ManageTokenAccount>) -> Result<()> {
+        instructions::disable_required_memo_transfers(ctx)
+    }
+
+    // DELEGATION FUNCTIONS
+
+    // Approve a delegate for a token account with an optional expiry (0 = never expires)
+    pub fn approve_delegate(
+        ctx: Context<ApproveDelegate>,
+        amount: u64,
+        expires_at: i64
+    ) -> Result<()> {
+        instructions::approve_token_delegate(ctx, amount, expires_at)
+    }
+
+    // Revoke a token account's delegate and close its allowance
+    pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+        instructions::revoke_token_delegate(ctx)
+    }
+
+    // Transfer as the approved delegate within the allowance
+    pub fn delegated_transfer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DelegatedTransfer<'info>>,
+        amount: u64
+    ) -> Result<()> {
+        instructions::delegated_transfer_tokens(ctx, amount)
+    }
+
+    // Burn as the approved delegate within the allowance
+    pub fn delegated_burn(ctx: Context<DelegatedBurn>, amount: u64) -> Result<()> {
+        instructions::delegated_burn_tokens(ctx, amount)
+    }
+
+    // AUTHORITY FUNCTIONS
+
+    // Rotate or revoke a mint authority
+    pub fn set_mint_authority(
+        ctx: Context<SetMintAuthority>,
+        authority_type: MintAuthorityType,
+        new_authority: Option<Pubkey>
+    ) -> Result<()> {
+        instructions::set_spl_mint_authority(ctx, authority_type, new_authority)
+    }
+
+    // Transfer ownership of a token account
+    pub fn set_token_account_owner(
+        ctx: Context<SetTokenAccountOwner>,
+        new_owner: Pubkey
+    ) -> Result<()> {
+        instructions::set_spl_token_account_owner(ctx, new_owner)
+    }
+
+    // KYC FUNCTIONS
+
+    // Gate a mint behind KYC and set its approver (freeze authority), or replace the approver
+    pub fn set_kyc_approver_admin(ctx: Context<SetKycApprover>, approver: Pubkey) -> Result<()> {
+        instructions::set_kyc_approver(ctx, approver)
+    }
+
+    // Approve a wallet's KYC until the given expiry
+    pub fn approve_kyc_wallet(ctx: Context<ApproveKyc>, expires_at: i64) -> Result<()> {
+        instructions::approve_kyc(ctx, expires_at)
+    }
+
+    // Revoke a wallet's KYC approval
+    pub fn revoke_kyc_wallet(ctx: Context<RevokeKyc>) -> Result<()> {
+        instructions::revoke_kyc(ctx)
+    }
+
+    // Thaw token account once its owner passed KYC (permissionless)
+    pub fn thaw_kyc_token_account(ctx: Context<ThawKycTokenAccount>) -> Result<()> {
+        instructions::thaw_kyc_approved_account(ctx)
+    }
+
+    // Freeze a token account of a KYC-gated mint (approver only)
+    pub fn freeze_kyc_token_account(ctx: Context<FreezeKycTokenAccount>) -> Result<()> {
+        instructions::freeze_kyc_gated_account(ctx)
+    }
+
+    // TRANSFER FEE FUNCTIONS
+
+    // Harvest withheld fees from token accounts into the mint
+    pub fn harvest_transfer_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, HarvestWithheldFees<'info>>
+    ) -> Result<()> {
+        instructions::harvest_withheld_fees(ctx)
+    }
+
+    // Withdraw withheld fees from the mint to a treasury account
+    pub fn withdraw_transfer_fees(ctx: Context<WithdrawWithheldFees>) -> Result<()> {
+        instructions::withdraw_withheld_fees(ctx)
+    }
+
+    // Update the transfer fee schedule (fee authority only)
+    pub fn update_transfer_fee_schedule(
+        ctx: Context<UpdateTransferFee>,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64
+    ) -> Result<()> {
+        instructions::update_transfer_fee(ctx, transfer_fee_basis_points, maximum_fee)
+    }
+
+    // INTEREST BEARING FUNCTIONS
+
+    // Update the interest rate (rate authority only)
+    pub fn update_interest_rate(ctx: Context<UpdateInterestRate>, rate: i16) -> Result<()> {
+        instructions::set_interest_rate(ctx, rate)
+    }
+
+    // Get the UI amount for a raw amount, including accrued interest
+    pub fn get_ui_amount(ctx: Context<UiAmount>, amount: u64) -> Result<String> {
+        instructions::calculate_ui_amount(ctx, amount)
+    }
+
+    // CONFIDENTIAL TRANSFER FUNCTIONS
+
+    // Enable confidential balances on a token account (proof verified by Token-2022)
+    pub fn configure_confidential_account(
+        ctx: Context<ConfigureConfidentialAccount>,
+        decryptable_zero_balance: [u8; 36],
+        maximum_pending_balance_credit_counter: u64,
+        proof_instruction_offset: i8
+    ) -> Result<()> {
+        instructions::configure_confidential_token_account(
+            ctx,
+            decryptable_zero_balance,
+            maximum_pending_balance_credit_counter,
+            proof_instruction_offset
+        )
+    }
+
+    // Approve a configured account (confidential transfer authority only)
+    pub fn approve_confidential_account(ctx: Context<ApproveConfidentialAccount>) -> Result<()> {
+        instructions::approve_confidential_token_account(ctx)
+    }
+
+    // Deposit public tokens into the pending confidential balance
+    pub fn deposit_confidential(ctx: Context<DepositConfidential>, amount: u64) -> Result<()> {
+        instructions::deposit_confidential_tokens(ctx, amount)
+    }
+
+    // Apply the pending confidential balance to the available balance
+    pub fn apply_pending_balance(
+        ctx: Context<ApplyPendingBalance>,
+        expected_pending_balance_credit_counter: u64,
+        new_decryptable_available_balance: [u8; 36]
+    ) -> Result<()> {
+        instructions::apply_pending_confidential_balance(
+            ctx,
+            expected_pending_balance_credit_counter,
+            new_decryptable_available_balance
+        )
+    }
+
+    // Withdraw from the available confidential balance to the public balance
+    pub fn withdraw_confidential(
+        ctx: Context<WithdrawConfidential>,
+        amount: u64,
+        new_decryptable_available_balance: [u8; 36],
+        proof_instruction_offset: i8
+    ) -> Result<()> {
+        instructions::withdraw_confidential_tokens(
+            ctx,
+            amount,
+            new_decryptable_available_balance,
+            proof_instruction_offset
+        )
+    }
+
+    // MINTER REGISTRY FUNCTIONS
+
+    // Add a minter or update its allowance, rate limit and enable flag (mint admin only)
+    pub fn add_minter(
+        ctx: Context<AddMinter>,
+        allowance: u64,
+        epoch_limit: u64,
+        enabled: bool
+    ) -> Result<()> {
+        instructions::configure_minter(ctx, allowance, epoch_limit, enabled)
+    }
+
+    // Remove a minter (mint admin only)
+    pub fn remove_minter(ctx: Context<RemoveMinter>) -> Result<()> {
+        instructions::delete_minter(ctx)
+    }
+
+    // Permanently give up minting for the mint (mint admin only)
+    pub fn renounce_mint_authority(ctx: Context<RenounceMintAuthority>) -> Result<()> {
+        instructions::revoke_program_mint_authority(ctx)
+    }
+
+    // VESTING FUNCTIONS
+
+    // Lock tokens in a vesting vault with a cliff and linear release
+    pub fn create_vesting<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateVesting<'info>>,
+        id: u64,
+        amount: u64,
+        schedule: VestingScheduleArgs
+    ) -> Result<()> {
+        instructions::create_vesting_schedule(ctx, id, amount, schedule)
+    }
+
+    // Release the unlocked portion to the beneficiary
+    pub fn release_vested<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReleaseVested<'info>>
+    ) -> Result<()> {
+        instructions::release_vested_tokens(ctx)
+    }
+
+    // Return unvested tokens to the grantor (revocable schedules only)
+    pub fn revoke_vesting<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RevokeVesting<'info>>
+    ) -> Result<()> {
+        instructions::revoke_vesting_schedule(ctx)
+    }
+
+    // AIRDROP FUNCTIONS
+
+    // Create and fund a merkle airdrop distributor
+    pub fn create_distributor<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateDistributor<'info>>,
+        id: u64,
+        merkle_root: [u8; 32],
+        num_recipients: u32,
+        total_amount: u64,
+        start_time: i64,
+        end_time: i64
+    ) -> Result<()> {
+        instructions::create_merkle_distributor(
+            ctx,
+            id,
+            merkle_root,
+            num_recipients,
+            total_amount,
+            start_time,
+            end_time
+        )
+    }
+
+    // Claim an airdrop allocation with its merkle proof
+    pub fn claim_airdrop<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimAirdrop<'info>>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>
+    ) -> Result<()> {
+        instructions::claim_airdrop_allocation(ctx, index, amount, proof)
+    }
+
+    // Return unclaimed tokens to the admin after the claim window (admin only)
+    pub fn clawback_airdrop<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClawbackAirdrop<'info>>
+    ) -> Result<()> {
+        instructions::clawback_unclaimed_airdrop(ctx)
+    }
+
+    // MULTISIG FUNCTIONS
+
+    // Create an M-of-N multisig for token administration
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8
+    ) -> Result<()> {
+        instructions::initialize_multisig(ctx, signers, threshold)
+    }
+
+    // Propose a mint, burn, freeze, metadata, minter or config authority action (multisig signers only)
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        action: MultisigAction,
+        expires_at: i64
+    ) -> Result<()> {
+        instructions::propose_action(ctx, action, expires_at)
+    }
+
+    // Approve a pending proposal (multisig signers only)
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        instructions::approve_action(ctx)
+    }
+
+    // Execute a proposal that reached the threshold
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        instructions::execute_action(ctx)
+    }
+
+    // Close an executed or expired proposal, refunding the proposer
+    pub fn close_proposal(ctx: Context<CloseProposal>) -> Result<()> {
+        instructions::close_settled_proposal(ctx)
+    }
+
+    // Make a multisig the mint config authority (config authority only)
+    pub fn transfer_config_authority(ctx: Context<TransferConfigAuthority>) -> Result<()> {
+        instructions::transfer_config_authority_to_multisig(ctx)
+    }
+
+    // SUPPLY CAP FUNCTIONS
+
+    // Lower the mint's maximum supply (config authority only)
+    pub fn lower_max_supply(ctx: Context<UpdateMaxSupply>, max_supply: u64) -> Result<()> {
+        instructions::set_max_supply(ctx, max_supply)
+    }
+
+    // Get the amount that can still be minted under the cap
+    pub fn get_remaining_supply(ctx: Context<RemainingSupply>) -> Result<u64> {
+        instructions::calculate_remaining_supply(ctx)
+    }
+
+    // PERMANENT DELEGATE FUNCTIONS
+
+    // Recover tokens from a holder into the delegate's account
+    pub fn clawback<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Clawback<'info>>,
+        amount: u64,
+        reason_code: u16
+    ) -> Result<()> {
+        instructions::clawback_tokens(ctx, amount, reason_code)
+    }
+
+    // Move tokens from a holder to a designated account
+    pub fn force_transfer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ForceTransfer<'info>>,
+        amount: u64,
+        reason_code: u16
+    ) -> Result<()> {
+        instructions::force_transfer_tokens(ctx, amount, reason_code)
+    }
+
+    // TOKEN GROUP FUNCTIONS
+
+    // Initialize a group-pointer mint as a token group with a maximum size
+    pub fn create_group(ctx: Context<CreateGroup>, max_size: u32) -> Result<()> {
+        instructions::initialize_token_group(ctx, max_size)
+    }
+
+    // Register a member-pointer mint in a token group
+    pub fn add_group_member(ctx: Context<AddGroupMember>) -> Result<()> {
+        instructions::add_token_group_member(ctx)
+    }
+
+    // TOKEN METADATA FUNCTIONS
+
+    // Update a metadata field or custom key
+    pub fn update_token_metadata_field(
+        ctx: Context<UpdateTokenMetadata>,
+        field: MetadataField,
+        value: String
+    ) -> Result<()> {
+        instructions::update_metadata_field(ctx, field, value)
+    }
+
+    // Remove a custom metadata key
+    pub fn remove_token_metadata_key(
+        ctx: Context<UpdateTokenMetadata>,
+        key: String,
+        idempotent: bool
+    ) -> Result<()> {
+        instructions::remove_metadata_key(ctx, key, idempotent)
+    }
+
+    // Rotate or renounce the metadata update authority
+    pub fn update_token_metadata_authority(
+        ctx: Context<UpdateTokenMetadata>,
+        new_authority: Option<Pubkey>
+    ) -> Result<()> {
+        instructions::update_metadata_authority(ctx, new_authority)
+    }
+
     // STAKING FUNCTIONS
 
     // Initialize staking pool with reward rate
@@ -72,17 +482,25 @@ pub mod spl {
     }
 
     // Stake tokens into the pool
-    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+    pub fn stake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Stake<'info>>,
+        amount: u64
+    ) -> Result<()> {
         instructions::stake_tokens(ctx, amount)
     }
 
     // Unstake tokens from the pool
-    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+    pub fn unstake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>,
+        amount: u64
+    ) -> Result<()> {
         instructions::unstake_tokens(ctx, amount)
     }
 
     // Claim accumulated rewards
-    pub fn claim_rewards_stake(ctx: Context<ClaimRewards>) -> Result<()> {
+    pub fn claim_rewards_stake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>
+    ) -> Result<()> {
         instructions::claim_rewards(ctx)
     }
 
@@ -107,7 +525,9 @@ pub mod spl {
     }
 
     // Emergency withdraw with penalty
-    pub fn emergency_withdraw_stake(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+    pub fn emergency_withdraw_stake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EmergencyWithdraw<'info>>
+    ) -> Result<()> {
         instructions::emergency_withdraw(ctx)
     }
 
@@ -123,8 +543,8 @@ pub mod spl {
     }
 
     // Add liquidity to pool
-    pub fn add_liquidity_amm(
-        ctx: Context<AddLiquidity>,
+    pub fn add_liquidity_amm<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'info>>,
         amount_a_desired: u64,
         max_amount_b: u64
     ) -> Result<()> {
@@ -132,7 +552,10 @@ pub mod spl {
     }
 
     // Remove liquidity from pool
-    pub fn remove_liquidity_amm(ctx: Context<RemoveLiquidity>, lp_amount: u64) -> Result<()> {
+    pub fn remove_liquidity_amm<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RemoveLiquidity<'info>>,
+        lp_amount: u64
+    ) -> Result<()> {
         instructions::remove_liquidity(ctx, lp_amount)
     }
 
@@ -142,7 +565,11 @@ pub mod spl {
     }
 
     // Execute token swap
-    pub fn swap_amm(ctx: Context<Swap>, amount_in: u64, min_out: u64) -> Result<()> {
+    pub fn swap_amm<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Swap<'info>>,
+        amount_in: u64,
+        min_out: u64
+    ) -> Result<()> {
         instructions::swap(ctx, amount_in, min_out)
     }
 }
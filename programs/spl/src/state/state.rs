@@ -1,11 +1,42 @@
 use anchor_lang::prelude::*;
 
+// Seed for token mint PDA
+pub const MINT_SEED: &[u8] = b"mint";
 // Seed for staking pool PDA
 pub const POOL_SEED: &[u8] = b"staking_pool";
 // Seed for user stake PDA
 pub const USER_STAKE_SEED: &[u8] = b"user_stake";
 // Seed for liquidity pool PDA
 pub const LIQUIDITY_POOL_SEED: &[u8] = b"liquidity_pool";
+// Seed for per-mint KYC config PDA
+pub const KYC_CONFIG_SEED: &[u8] = b"kyc_config";
+// Seed for per-wallet KYC record PDA
+pub const KYC_RECORD_SEED: &[u8] = b"kyc_record";
+// Seed for the PDA that holds the freeze authority of KYC-gated mints
+pub const KYC_FREEZE_AUTHORITY_SEED: &[u8] = b"kyc_freeze_authority";
+// Seed for per-authority mint registry PDA
+pub const MINT_REGISTRY_SEED: &[u8] = b"mint_registry";
+// Seed for per-mint config PDA
+pub const MINT_CONFIG_SEED: &[u8] = b"mint_config";
+// Seed for the program PDA that holds each mint's mint authority
+pub const MINT_AUTHORITY_SEED: &[u8] = b"mint_authority";
+// Seed for per-minter registry entry PDA
+pub const MINTER_SEED: &[u8] = b"minter";
+// Seed for multisig PDA
+pub const MULTISIG_SEED: &[u8] = b"multisig";
+// Seed for multisig proposal PDA
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+// Seed for vesting schedule PDA
+pub const VESTING_SEED: &[u8] = b"vesting";
+// Seed for merkle airdrop distributor PDA
+pub const DISTRIBUTOR_SEED: &[u8] = b"distributor";
+// Seed for per-token-account delegate allowance PDA
+pub const DELEGATE_ALLOWANCE_SEED: &[u8] = b"delegate_allowance";
+
+// Maximum number of signers in a multisig
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+// Maximum recipients per distributor (keeps the claimed bitmap within the 10 KiB init limit)
+pub const MAX_AIRDROP_RECIPIENTS: u32 = 80_000;
 
 /// STAKING POOL STATE
 #[account]
@@ -96,3 +127,368 @@ pub struct LiquidityPoolAMM {
     /// PDA bump for pool authority
     pub bump: u8,
 }
+
+/// KYC CONFIG STATE
+#[account]
+pub struct KycConfig {
+    /// Mint whose holders are onboarded through this config
+    pub mint: Pubkey,
+
+    /// Former freeze authority that gated the mint and may replace the approver
+    pub authority: Pubkey,
+
+    /// Wallet allowed to approve and revoke KYC records
+    pub approver: Pubkey,
+
+    /// PDA bump for the KYC config
+    pub bump: u8,
+
+    /// PDA bump for the freeze authority PDA
+    pub freeze_authority_bump: u8,
+}
+
+/// Each wallet's KYC approval for a mint
+#[account]
+pub struct KycRecord {
+    /// Mint the approval applies to
+    pub mint: Pubkey,
+
+    /// Approved wallet (token account owner)
+    pub wallet: Pubkey,
+
+    /// Approver that wrote this record
+    pub approver: Pubkey,
+
+    /// Timestamp of the latest approval
+    pub approved_at: i64,
+
+    /// Timestamp after which the approval is no longer valid (0 = never expires)
+    pub expires_at: i64,
+
+    /// PDA bump for the KYC record
+    pub bump: u8,
+}
+
+/// MINT REGISTRY STATE
+#[account]
+pub struct MintRegistry {
+    /// Authority whose mints are enumerated here
+    pub authority: Pubkey,
+
+    /// Every mint created by the authority, in creation order
+    pub mints: Vec<Pubkey>,
+
+    /// PDA bump for the registry
+    pub bump: u8,
+}
+
+impl MintRegistry {
+    // Account size (including discriminator) for a registry holding `mints` entries
+    pub fn space(mints: usize) -> usize {
+        8 + 32 + 4 + mints * 32 + 1
+    }
+}
+
+/// MINT CONFIG STATE
+#[account]
+pub struct MintConfig {
+    /// Mint this config applies to
+    pub mint: Pubkey,
+
+    /// Admin allowed to lower the supply cap and manage minters
+    pub authority: Pubkey,
+
+    /// Wallet that created the mint (its registry lists the mint)
+    pub creator: Pubkey,
+
+    /// Hard cap on the mint's supply (u64::MAX = uncapped)
+    pub max_supply: u64,
+
+    /// PDA bump for the mint config
+    pub bump: u8,
+}
+
+impl MintConfig {
+    // Amount that can still be minted given the mint's current supply
+    pub fn remaining_supply(&self, supply: u64) -> u64 {
+        self.max_supply.saturating_sub(supply)
+    }
+}
+
+/// Each minter's scoped minting rights for a mint
+#[account]
+pub struct Minter {
+    /// Mint the minter may mint
+    pub mint: Pubkey,
+
+    /// Wallet that signs mint requests
+    pub authority: Pubkey,
+
+    /// Remaining amount this minter may mint in total
+    pub allowance: u64,
+
+    /// Maximum amount mintable per epoch (0 = no limit)
+    pub epoch_limit: u64,
+
+    /// Epoch of the current rate-limit window
+    pub epoch: u64,
+
+    /// Amount minted during the current epoch
+    pub minted_this_epoch: u64,
+
+    /// Whether the minter may currently mint
+    pub enabled: bool,
+
+    /// PDA bump for the minter entry
+    pub bump: u8,
+}
+
+/// MULTISIG STATE
+/// Metadata field to update; `Key` adds or replaces a custom additional-metadata entry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum MetadataField {
+    Name,
+    Symbol,
+    Uri,
+    Key(String),
+}
+
+/// Token administration action a proposal executes, with its serialized parameters
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum MultisigAction {
+    /// Mint through the minter registry (the multisig must be a registered minter)
+    MintTokens {
+        mint: Pubkey,
+        to: Pubkey,
+        amount: u64,
+    },
+    /// Burn from a token account owned by the multisig
+    BurnTokens {
+        mint: Pubkey,
+        from: Pubkey,
+        amount: u64,
+    },
+    /// Freeze a token account (the multisig must be the freeze authority)
+    FreezeTokenAccount {
+        mint: Pubkey,
+        account: Pubkey,
+    },
+    /// Update a metadata field (the multisig must be the metadata update authority)
+    UpdateMetadataField {
+        mint: Pubkey,
+        field: MetadataField,
+        value: String,
+    },
+    /// Update a registered minter (the multisig must be the mint config authority)
+    ConfigureMinter {
+        mint: Pubkey,
+        minter: Pubkey,
+        allowance: u64,
+        epoch_limit: u64,
+        enabled: bool,
+    },
+    /// Hand the mint config authority on (the multisig must be the mint config authority)
+    TransferConfigAuthority {
+        mint: Pubkey,
+        new_authority: Pubkey,
+    },
+}
+
+impl MultisigAction {
+    // Mint the action applies to
+    pub fn mint(&self) -> Pubkey {
+        match self {
+            MultisigAction::MintTokens { mint, .. } => *mint,
+            MultisigAction::BurnTokens { mint, .. } => *mint,
+            MultisigAction::FreezeTokenAccount { mint, .. } => *mint,
+            MultisigAction::UpdateMetadataField { mint, .. } => *mint,
+            MultisigAction::ConfigureMinter { mint, .. } => *mint,
+            MultisigAction::TransferConfigAuthority { mint, .. } => *mint,
+        }
+    }
+}
+
+#[account]
+pub struct Multisig {
+    /// One-off key the multisig PDA is derived from
+    pub create_key: Pubkey,
+
+    /// Wallets allowed to propose and approve
+    pub signers: Vec<Pubkey>,
+
+    /// Approvals required to execute a proposal
+    pub threshold: u8,
+
+    /// Number of proposals created so far (index of the next proposal)
+    pub proposal_count: u64,
+
+    /// PDA bump for the multisig
+    pub bump: u8,
+}
+
+impl Multisig {
+    // Account size (including discriminator) for a multisig with `signers` members
+    pub fn space(signers: usize) -> usize {
+        8 + 32 + 4 + signers * 32 + 1 + 8 + 1
+    }
+}
+
+/// Each pending or executed multisig proposal
+#[account]
+pub struct Proposal {
+    /// Multisig the proposal belongs to
+    pub multisig: Pubkey,
+
+    /// Signer that created the proposal
+    pub proposer: Pubkey,
+
+    /// Sequential index within the multisig
+    pub index: u64,
+
+    /// Action to execute, with its serialized parameters
+    pub action: MultisigAction,
+
+    /// Signers that have approved so far
+    pub approvals: Vec<Pubkey>,
+
+    /// Timestamp after which the proposal can no longer be approved or executed
+    pub expires_at: i64,
+
+    /// Whether the proposal has been executed
+    pub executed: bool,
+
+    /// PDA bump for the proposal
+    pub bump: u8,
+}
+
+impl Proposal {
+    // Account size (including discriminator) for `action` with room for every signer's approval
+    pub fn space(action: &MultisigAction, signers: usize) -> usize {
+        let action_len = action.try_to_vec().map_or(0, |data| data.len());
+        8 + 32 + 32 + 8 + action_len + 4 + signers * 32 + 8 + 1 + 1
+    }
+}
+
+/// VESTING SCHEDULE STATE
+#[account]
+pub struct VestingSchedule {
+    /// Wallet that funded the schedule (receives revoked tokens)
+    pub grantor: Pubkey,
+
+    /// Wallet that receives vested tokens
+    pub beneficiary: Pubkey,
+
+    /// Mint of the vested token
+    pub mint: Pubkey,
+
+    /// Vault (owned by this PDA) holding the locked tokens
+    pub vault: Pubkey,
+
+    /// Caller-chosen id so a beneficiary can hold several schedules per mint
+    pub id: u64,
+
+    /// Total amount vesting under this schedule
+    pub total_amount: u64,
+
+    /// Amount already paid out to the beneficiary
+    pub released_amount: u64,
+
+    /// Timestamp vesting is measured from
+    pub start_time: i64,
+
+    /// Nothing is releasable before this timestamp
+    pub cliff_time: i64,
+
+    /// Everything is releasable from this timestamp
+    pub end_time: i64,
+
+    /// Tokens unlock in steps of this many seconds
+    pub period: i64,
+
+    /// Whether the grantor may revoke the unvested remainder
+    pub revocable: bool,
+
+    /// Whether the schedule has been revoked
+    pub revoked: bool,
+
+    /// PDA bump for the vesting schedule
+    pub bump: u8,
+}
+
+/// MERKLE DISTRIBUTOR STATE
+#[account]
+pub struct Distributor {
+    /// Wallet that funded the airdrop (receives the clawback)
+    pub admin: Pubkey,
+
+    /// Mint being airdropped
+    pub mint: Pubkey,
+
+    /// Vault (owned by this PDA) holding unclaimed tokens
+    pub vault: Pubkey,
+
+    /// Caller-chosen id so an admin can run several airdrops per mint
+    pub id: u64,
+
+    /// Root of the tree over every (index, claimant, amount) allocation
+    pub merkle_root: [u8; 32],
+
+    /// Number of leaves in the tree
+    pub num_recipients: u32,
+
+    /// Amount deposited into the vault
+    pub total_amount: u64,
+
+    /// Amount claimed so far
+    pub claimed_amount: u64,
+
+    /// Number of allocations claimed so far
+    pub num_claimed: u32,
+
+    /// Claims open at this timestamp
+    pub start_time: i64,
+
+    /// Claims close (and clawback opens) at this timestamp
+    pub end_time: i64,
+
+    /// Whether unclaimed tokens have been returned to the admin
+    pub clawed_back: bool,
+
+    /// PDA bump for the distributor
+    pub bump: u8,
+
+    /// One bit per leaf index, set once that allocation is claimed
+    pub claimed_bitmap: Vec<u8>,
+}
+
+impl Distributor {
+    // Account size (including discriminator) for a distributor with `num_recipients` leaves
+    pub fn space(num_recipients: u32) -> usize {
+        8 + 32 * 3 + 8 + 32 + 4 + 8 + 8 + 4 + 8 + 8 + 1 + 1 + 4 + Self::bitmap_len(num_recipients)
+    }
+}
+
+/// DELEGATE ALLOWANCE STATE
+#[account]
+pub struct DelegateAllowance {
+    /// Token account the delegate may spend from
+    pub token_account: Pubkey,
+
+    /// Mint of the token account
+    pub mint: Pubkey,
+
+    /// Owner of the token account that granted the allowance
+    pub owner: Pubkey,
+
+    /// Wallet allowed to pull tokens through this allowance
+    pub delegate: Pubkey,
+
+    /// Amount the delegate may still transfer or burn
+    pub remaining_amount: u64,
+
+    /// Timestamp after which the allowance can no longer be used (0 = never expires)
+    pub expires_at: i64,
+
+    /// PDA bump for the allowance
+    pub bump: u8,
+}
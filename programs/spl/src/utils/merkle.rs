@@ -0,0 +1,154 @@
+use anchor_lang::{ prelude::*, solana_program::hash::hashv };
+
+// Domain-separation prefixes so a leaf can never be replayed as an inner node
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+// Leaf committing to one airdrop allocation
+pub fn airdrop_leaf(index: u32, claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, &index.to_le_bytes(), claimant.as_ref(), &amount.to_le_bytes()]).to_bytes()
+}
+
+// Parent of two nodes; pairs are sorted so proofs need no left/right flags
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, first, second]).to_bytes()
+}
+
+// Check that `leaf` is part of the tree with the given `root`
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    computed == *root
+}
+
+/// Off-chain builder for airdrop trees and their proofs
+#[cfg(not(target_os = "solana"))]
+pub struct MerkleTree {
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl MerkleTree {
+    // Build a tree over `(claimant, amount)` allocations; a recipient's index is its position
+    pub fn from_allocations(allocations: &[(Pubkey, u64)]) -> Self {
+        let leaves = allocations
+            .iter()
+            .enumerate()
+            .map(|(index, (claimant, amount))| airdrop_leaf(index as u32, claimant, *amount))
+            .collect();
+        Self::new(leaves)
+    }
+
+    // Build a tree over precomputed leaves; an odd node out is carried up unchanged
+    pub fn new(leaves: Vec<[u8; 32]>) -> Self {
+        let mut layers = vec![leaves];
+        while layers[layers.len() - 1].len() > 1 {
+            let next = layers[layers.len() - 1]
+                .chunks(2)
+                .map(|pair| if pair.len() == 2 { hash_pair(&pair[0], &pair[1]) } else { pair[0] })
+                .collect();
+            layers.push(next);
+        }
+        Self { layers }
+    }
+
+    // Root to store in the distributor (all zeroes for an empty tree)
+    pub fn root(&self) -> [u8; 32] {
+        self.layers
+            .last()
+            .and_then(|layer| layer.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    // Sibling hashes from the leaf at `index` up to the root
+    pub fn proof(&self, index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::new();
+        let mut position = index;
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let sibling = position ^ 1;
+            if sibling < layer.len() {
+                proof.push(layer[sibling]);
+            }
+            position /= 2;
+        }
+        proof
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allocations(count: usize) -> Vec<(Pubkey, u64)> {
+        (0..count).map(|i| (Pubkey::new_unique(), 100 * (i as u64 + 1))).collect()
+    }
+
+    #[test]
+    fn every_proof_verifies_against_the_root() {
+        for count in 1..=9 {
+            let allocations = allocations(count);
+            let tree = MerkleTree::from_allocations(&allocations);
+            for (index, (claimant, amount)) in allocations.iter().enumerate() {
+                let leaf = airdrop_leaf(index as u32, claimant, *amount);
+                assert!(verify_merkle_proof(&tree.proof(index), &tree.root(), leaf));
+            }
+        }
+    }
+
+    #[test]
+    fn single_leaf_tree_is_its_own_root() {
+        let allocations = allocations(1);
+        let tree = MerkleTree::from_allocations(&allocations);
+        let leaf = airdrop_leaf(0, &allocations[0].0, allocations[0].1);
+        assert_eq!(tree.root(), leaf);
+        assert!(tree.proof(0).is_empty());
+    }
+
+    #[test]
+    fn empty_tree_has_zero_root() {
+        assert_eq!(MerkleTree::new(Vec::new()).root(), [0u8; 32]);
+    }
+
+    #[test]
+    fn rejects_tampered_proof() {
+        let allocations = allocations(5);
+        let tree = MerkleTree::from_allocations(&allocations);
+        let (claimant, amount) = allocations[2];
+        let leaf = airdrop_leaf(2, &claimant, amount);
+
+        let mut proof = tree.proof(2);
+        proof[0][0] ^= 1;
+        assert!(!verify_merkle_proof(&proof, &tree.root(), leaf));
+
+        let truncated = &tree.proof(2)[1..];
+        assert!(!verify_merkle_proof(truncated, &tree.root(), leaf));
+
+        // A sibling's proof does not prove this leaf
+        assert!(!verify_merkle_proof(&tree.proof(0), &tree.root(), leaf));
+    }
+
+    #[test]
+    fn rejects_wrong_index_amount_or_claimant() {
+        let allocations = allocations(4);
+        let tree = MerkleTree::from_allocations(&allocations);
+        let (claimant, amount) = allocations[1];
+        let proof = tree.proof(1);
+
+        assert!(!verify_merkle_proof(&proof, &tree.root(), airdrop_leaf(0, &claimant, amount)));
+        assert!(!verify_merkle_proof(&proof, &tree.root(), airdrop_leaf(1, &claimant, amount + 1)));
+        assert!(
+            !verify_merkle_proof(&proof, &tree.root(), airdrop_leaf(1, &Pubkey::new_unique(), amount))
+        );
+    }
+
+    #[test]
+    fn leaves_cannot_pose_as_inner_nodes() {
+        let allocations = allocations(2);
+        let tree = MerkleTree::from_allocations(&allocations);
+        let left = airdrop_leaf(0, &allocations[0].0, allocations[0].1);
+        let right = airdrop_leaf(1, &allocations[1].0, allocations[1].1);
+        assert_eq!(tree.root(), hash_pair(&left, &right));
+        assert_ne!(tree.root(), hashv(&[LEAF_PREFIX, &left, &right]).to_bytes());
+    }
+}
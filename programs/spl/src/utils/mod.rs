@@ -1,3 +1,6 @@
+pub mod merkle;
+#[allow(clippy::module_inception)]
 pub mod utils;
 
+pub use merkle::*;
 pub use utils::*;
@@ -1,34 +1,40 @@
 use anchor_lang::{
-    prelude::Result,
-    solana_program::{
-        account_info::AccountInfo, program::invoke, pubkey::Pubkey, rent::Rent,
-        system_instruction::transfer, sysvar::Sysvar,
-    },
-    Lamports,
+    prelude::{CpiContext, Result},
+    solana_program::account_info::AccountInfo,
 };
-use anchor_spl::token_interface::spl_token_2022::{
-    extension::{BaseStateWithExtensions, Extension, StateWithExtensions},
-    solana_zk_token_sdk::zk_token_proof_instruction::Pod,
-    state::Mint,
+use anchor_spl::token_interface::{
+    spl_token_2022::{
+        extension::{BaseStateWithExtensions, Extension, ExtensionType, StateWithExtensions},
+        onchain::invoke_transfer_checked,
+        solana_zk_token_sdk::zk_token_proof_instruction::Pod,
+        state::{Account, Mint},
+    },
+    TransferChecked,
 };
-use spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList};
 use spl_type_length_value::variable_len_pack::VariableLenPack;
 
-pub const APPROVE_ACCOUNT_SEED: &[u8] = b"approve-account";
-pub const META_LIST_ACCOUNT_SEED: &[u8] = b"extra-account-metas";
+// Rent and account-closing helpers live in the hook crate, which both programs build on
+pub use spl_hook::utils::{close_program_account, update_account_lamports_to_minimum_balance};
 
-pub fn update_account_lamports_to_minimum_balance<'info>(
-    account: AccountInfo<'info>,
-    payer: AccountInfo<'info>,
-    system_program: AccountInfo<'info>,
+// `transfer_checked` that also works for mints with a transfer hook: Token-2022 needs the hook
+// program, the mint's extra-account-metas list and every account listed in it, which are
+// looked up by address in `ctx.remaining_accounts` (ignored for mints without a hook)
+pub fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
 ) -> Result<()> {
-    let extra_lamports = Rent::get()?.minimum_balance(account.data_len()) - account.get_lamports();
-    if extra_lamports > 0 {
-        invoke(
-            &transfer(payer.key, account.key, extra_lamports),
-            &[payer, account, system_program],
-        )?;
-    }
+    invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )?;
     Ok(())
 }
 
@@ -48,19 +54,27 @@ pub fn get_mint_extension_data<T: Extension + Pod>(account: &mut AccountInfo) ->
     Ok(extension_data)
 }
 
-pub fn get_meta_list(approve_account: Option<Pubkey>) -> Vec<ExtraAccountMeta> {
-    if let Some(approve_account) = approve_account {
-        return vec![ExtraAccountMeta {
-            discriminator: 0,
-            address_config: approve_account.to_bytes(),
-            is_signer: false.into(),
-            is_writable: true.into(),
-        }];
-    }
-    vec![]
+// Whether the mint carries the NonTransferable (soulbound) extension
+pub fn is_non_transferable_mint(account: &AccountInfo) -> Result<bool> {
+    let mint_data = account.data.borrow();
+    let mint_with_extension = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    Ok(mint_with_extension
+        .get_extension_types()?
+        .contains(&ExtensionType::NonTransferable))
+}
+
+// Every extension initialized on a mint (empty for classic SPL Token mints)
+pub fn get_mint_extension_types(account: &AccountInfo) -> Result<Vec<ExtensionType>> {
+    let mint_data = account.data.borrow();
+    let mint_with_extension = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    Ok(mint_with_extension.get_extension_types()?)
 }
 
-pub fn get_meta_list_size(approve_account: Option<Pubkey>) -> usize {
-    // safe because it's either 0 or 1
-    ExtraAccountMetaList::size_of(get_meta_list(approve_account).len()).unwrap()
+// Whether the token account carries the ImmutableOwner extension
+pub fn has_immutable_owner(account: &AccountInfo) -> Result<bool> {
+    let account_data = account.data.borrow();
+    let account_with_extension = StateWithExtensions::<Account>::unpack(&account_data)?;
+    Ok(account_with_extension
+        .get_extension_types()?
+        .contains(&ExtensionType::ImmutableOwner))
 }
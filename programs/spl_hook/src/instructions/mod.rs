@@ -0,0 +1,6 @@
+pub mod policy;
+pub mod transfer_hook;
+pub mod velocity;
+pub use policy::*;
+pub use transfer_hook::*;
+pub use velocity::*;
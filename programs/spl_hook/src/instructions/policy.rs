@@ -0,0 +1,324 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use std::mem::size_of;
+
+use crate::{ state::*, utils::* };
+
+/// MINT POLICY FUNCTIONS
+// Create the mint's policy account and extend the hook's meta list so every transfer
+// also passes the policy accounts (see `get_policy_meta_list`)
+pub fn initialize_mint_policy(ctx: Context<InitializeMintPolicy>) -> Result<()> {
+    let policy = &mut ctx.accounts.policy;
+    policy.mint = ctx.accounts.mint.key();
+    policy.authority = ctx.accounts.authority.key();
+    policy.paused = false;
+    policy.blocked_count = 0;
+    policy.bump = ctx.bumps.policy;
+
+    write_policy_meta_list(
+        ctx.accounts.extra_metas_account.to_account_info(),
+        ctx.accounts.approve_account.key(),
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    emit!(MintPolicyInitialized {
+        mint: policy.mint,
+        authority: policy.authority,
+    });
+    Ok(())
+}
+
+// Halt or resume every transfer of the mint
+pub fn set_transfers_paused(ctx: Context<UpdateMintPolicy>, paused: bool) -> Result<()> {
+    let policy = &mut ctx.accounts.policy;
+    policy.paused = paused;
+
+    emit!(TransfersPauseSet {
+        mint: policy.mint,
+        authority: ctx.accounts.authority.key(),
+        paused,
+    });
+    Ok(())
+}
+
+// Block a wallet from sending or receiving the mint, growing its page by one entry
+pub fn add_to_blocklist(ctx: Context<AddToBlocklist>) -> Result<()> {
+    let wallet = ctx.accounts.wallet.key();
+    let page = &mut ctx.accounts.page;
+    if page.mint == Pubkey::default() {
+        page.mint = ctx.accounts.mint.key();
+        page.page = BlocklistPage::page_of(&wallet);
+        page.bump = ctx.bumps.page;
+    }
+    require!(!page.owners.contains(&wallet), PolicyError::AlreadyBlocked);
+    page.owners.push(wallet);
+
+    let page_info = page.to_account_info();
+    page_info.realloc(BlocklistPage::space(page.owners.len()), false)?;
+    update_account_lamports_to_minimum_balance(
+        page_info,
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    let policy = &mut ctx.accounts.policy;
+    policy.blocked_count = policy.blocked_count
+        .checked_add(1)
+        .ok_or(PolicyError::MathOverflow)?;
+
+    emit!(BlocklistUpdated {
+        mint: policy.mint,
+        wallet,
+        page: page.page,
+        blocked: true,
+    });
+    Ok(())
+}
+
+// Unblock a wallet, shrinking its page and refunding the freed rent to the authority
+pub fn remove_from_blocklist(ctx: Context<RemoveFromBlocklist>) -> Result<()> {
+    let wallet = ctx.accounts.wallet.key();
+    let page = &mut ctx.accounts.page;
+    let index = page.owners
+        .iter()
+        .position(|owner| owner == &wallet)
+        .ok_or(PolicyError::NotBlocked)?;
+    page.owners.swap_remove(index);
+
+    let page_info = page.to_account_info();
+    page_info.realloc(BlocklistPage::space(page.owners.len()), false)?;
+    let excess = page_info
+        .get_lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(page_info.data_len()));
+    if excess > 0 {
+        page_info.sub_lamports(excess)?;
+        ctx.accounts.authority.add_lamports(excess)?;
+    }
+
+    let policy = &mut ctx.accounts.policy;
+    policy.blocked_count = policy.blocked_count.saturating_sub(1);
+
+    emit!(BlocklistUpdated {
+        mint: policy.mint,
+        wallet,
+        page: page.page,
+        blocked: false,
+    });
+    Ok(())
+}
+
+// Enforce the pause flag and blocklist inside the transfer hook. `policy_accounts` are the
+// extra accounts after the approve account; mints that never created a policy pass none.
+// A velocity config created without a policy leaves the policy PDA empty, which is skipped.
+pub fn enforce_mint_policy(
+    mint: &Pubkey,
+    source_owner: &Pubkey,
+    destination_owner: &Pubkey,
+    policy_accounts: &[AccountInfo]
+) -> Result<()> {
+    let [policy_info, source_page_info, destination_page_info, ..] = policy_accounts else {
+        return Ok(());
+    };
+    if policy_info.data_is_empty() {
+        return Ok(());
+    }
+
+    require_keys_eq!(*policy_info.owner, crate::ID, PolicyError::InvalidPolicyAccount);
+    let policy = MintPolicy::try_deserialize(&mut &policy_info.try_borrow_data()?[..])?;
+    require_keys_eq!(policy.mint, *mint, PolicyError::InvalidPolicyAccount);
+    require!(!policy.paused, PolicyError::TransfersPaused);
+
+    require!(
+        !is_blocked(mint, source_owner, source_page_info)?,
+        PolicyError::SourceBlocked
+    );
+    require!(
+        !is_blocked(mint, destination_owner, destination_page_info)?,
+        PolicyError::DestinationBlocked
+    );
+    Ok(())
+}
+
+// Whether `owner` is listed on its blocklist page (a page never written to has no data)
+fn is_blocked(mint: &Pubkey, owner: &Pubkey, page_info: &AccountInfo) -> Result<bool> {
+    if page_info.data_is_empty() {
+        return Ok(false);
+    }
+    require_keys_eq!(*page_info.owner, crate::ID, PolicyError::InvalidPolicyAccount);
+    let page = BlocklistPage::try_deserialize(&mut &page_info.try_borrow_data()?[..])?;
+    require!(
+        page.mint == *mint && page.page == BlocklistPage::page_of(owner),
+        PolicyError::InvalidPolicyAccount
+    );
+    Ok(page.owners.contains(owner))
+}
+
+// Close the policy and its blocklist pages along with the mint. Every page still listing an
+// owner must be among `pages` so no blocklist entry outlives the policy.
+pub fn close_mint_policy<'info>(
+    mint: &Pubkey,
+    policy_info: &AccountInfo<'info>,
+    pages: &[AccountInfo<'info>],
+    destination: &AccountInfo<'info>
+) -> Result<()> {
+    if policy_info.owner != &crate::ID {
+        require!(pages.is_empty(), PolicyError::InvalidPolicyAccount);
+        return Ok(());
+    }
+    let policy = MintPolicy::try_deserialize(&mut &policy_info.try_borrow_data()?[..])?;
+
+    let mut listed: u64 = 0;
+    for page_info in pages {
+        require_keys_eq!(*page_info.owner, crate::ID, PolicyError::InvalidPolicyAccount);
+        let page = BlocklistPage::try_deserialize(&mut &page_info.try_borrow_data()?[..])?;
+        let page_key = Pubkey::create_program_address(
+            &[BLOCKLIST_SEED, mint.as_ref(), &[page.page], &[page.bump]],
+            &crate::ID
+        ).map_err(|_| PolicyError::InvalidPolicyAccount)?;
+        require!(
+            page.mint == *mint && page_key == page_info.key(),
+            PolicyError::InvalidPolicyAccount
+        );
+        listed = listed.checked_add(page.owners.len() as u64).ok_or(PolicyError::MathOverflow)?;
+        close_program_account(page_info, destination)?;
+    }
+    require!(listed == policy.blocked_count, PolicyError::BlocklistPagesMissing);
+
+    close_program_account(policy_info, destination)
+}
+
+/// MINT POLICY ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct InitializeMintPolicy<'info> {
+    /// Transfer hook authority of the mint (pays for the policy and the larger meta list)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [APPROVE_ACCOUNT_SEED, mint.key().as_ref()],
+        bump = approve_account.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub approve_account: Box<Account<'info, ApproveAccount>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [MINT_POLICY_SEED, mint.key().as_ref()],
+        bump,
+        space = 8 + size_of::<MintPolicy>()
+    )]
+    pub policy: Box<Account<'info, MintPolicy>>,
+    /// CHECK: ExtraAccountMetaList account, rewritten with the policy accounts
+    #[account(mut, seeds = [META_LIST_ACCOUNT_SEED, mint.key().as_ref()], bump)]
+    pub extra_metas_account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMintPolicy<'info> {
+    pub authority: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [MINT_POLICY_SEED, mint.key().as_ref()],
+        bump = policy.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub policy: Box<Account<'info, MintPolicy>>,
+}
+
+#[derive(Accounts)]
+pub struct AddToBlocklist<'info> {
+    /// Policy authority (pays for the page)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [MINT_POLICY_SEED, mint.key().as_ref()],
+        bump = policy.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub policy: Box<Account<'info, MintPolicy>>,
+    /// CHECK: wallet to block; only its key is stored
+    pub wallet: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [BLOCKLIST_SEED, mint.key().as_ref(), &[BlocklistPage::page_of(wallet.key)]],
+        bump,
+        space = BlocklistPage::space(0)
+    )]
+    pub page: Box<Account<'info, BlocklistPage>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromBlocklist<'info> {
+    /// Policy authority (receives the rent freed by the smaller page)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [MINT_POLICY_SEED, mint.key().as_ref()],
+        bump = policy.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub policy: Box<Account<'info, MintPolicy>>,
+    /// CHECK: wallet to unblock; only its key is compared
+    pub wallet: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [BLOCKLIST_SEED, mint.key().as_ref(), &[BlocklistPage::page_of(wallet.key)]],
+        bump = page.bump,
+        has_one = mint
+    )]
+    pub page: Box<Account<'info, BlocklistPage>>,
+}
+
+#[event]
+pub struct MintPolicyInitialized {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct TransfersPauseSet {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct BlocklistUpdated {
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+    pub page: u8,
+    pub blocked: bool,
+}
+
+#[error_code]
+pub enum PolicyError {
+    #[msg("transfers of this mint are paused")]
+    TransfersPaused,
+    #[msg("source owner is blocklisted")]
+    SourceBlocked,
+    #[msg("destination owner is blocklisted")]
+    DestinationBlocked,
+    #[msg("wallet is already blocklisted")]
+    AlreadyBlocked,
+    #[msg("wallet is not blocklisted")]
+    NotBlocked,
+    #[msg("policy or blocklist account does not belong to this mint")]
+    InvalidPolicyAccount,
+    #[msg("math overflow")]
+    MathOverflow,
+    #[msg("every blocklist page listing an owner must be closed with the policy")]
+    BlocklistPagesMissing,
+}
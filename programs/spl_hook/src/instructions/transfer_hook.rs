@@ -0,0 +1,226 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use std::mem::size_of;
+
+use crate::{
+    instructions::{
+        close_mint_policy, close_velocity_accounts, enforce_mint_policy, enforce_velocity_limit,
+        is_velocity_counter,
+    },
+    state::*,
+    utils::*,
+};
+
+/// TRANSFER HOOK FUNCTIONS
+// Create the approve account and the meta list pointing at it for a mint whose transfer hook
+// is this program (called by the spl program while it creates the mint)
+pub fn initialize_hook_accounts(ctx: Context<InitializeHook>) -> Result<()> {
+    let approve_account = &mut ctx.accounts.approve_account;
+    approve_account.mint = ctx.accounts.mint.key();
+    approve_account.authority = ctx.accounts.authority.key();
+    approve_account.max_transfer_amount = 0;
+    approve_account.bump = ctx.bumps.approve_account;
+
+    ExtraAccountMetaList::init::<ExecuteInstruction>(
+        &mut ctx.accounts.extra_metas_account.try_borrow_mut_data()?,
+        &get_meta_list(approve_account.key()),
+    )?;
+    Ok(())
+}
+
+// Validate a transfer of a hooked mint (invoked by Token-2022 through the fallback)
+pub fn execute_transfer_hook(ctx: Context<TransferHookExecute>, amount: u64) -> Result<()> {
+    // Only accept calls made by the token program in the middle of a transfer
+    require!(
+        is_transferring(&ctx.accounts.source_token.to_account_info())?,
+        TransferHookError::NotTransferring
+    );
+
+    // Pause flag and blocklist, then the source owner's rolling outflow, when the mint has
+    // the policy accounts in its meta list (see `get_policy_meta_list` for the order)
+    let mint_key = ctx.accounts.mint.key();
+    let policy_accounts = ctx.remaining_accounts;
+    enforce_mint_policy(
+        &mint_key,
+        &ctx.accounts.source_token.owner,
+        &ctx.accounts.destination_token.owner,
+        policy_accounts,
+    )?;
+    enforce_velocity_limit(
+        &mint_key,
+        &ctx.accounts.source_token.owner,
+        policy_accounts.get(3..).unwrap_or_default(),
+        amount,
+    )?;
+
+    let approve_account = &ctx.accounts.approve_account;
+    require!(
+        approve_account.max_transfer_amount == 0 || amount <= approve_account.max_transfer_amount,
+        TransferHookError::TransferLimitExceeded
+    );
+    Ok(())
+}
+
+// Update the per-transfer policy enforced by the hook (authority only)
+pub fn set_transfer_policy(
+    ctx: Context<UpdateTransferPolicy>,
+    max_transfer_amount: u64,
+) -> Result<()> {
+    let approve_account = &mut ctx.accounts.approve_account;
+    approve_account.max_transfer_amount = max_transfer_amount;
+
+    emit!(TransferPolicyUpdated {
+        mint: approve_account.mint,
+        authority: ctx.accounts.authority.key(),
+        max_transfer_amount,
+    });
+    Ok(())
+}
+
+// Close the hook accounts of an empty mint, refunding their rent (close authority only).
+// Blocklist pages and velocity counters of the mint are passed as remaining accounts.
+pub fn close_hook_accounts<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseHookAccounts<'info>>,
+) -> Result<()> {
+    let mint_key = ctx.accounts.mint.key();
+    let destination = ctx.accounts.destination.to_account_info();
+    let (counters, pages): (Vec<_>, Vec<_>) = ctx
+        .remaining_accounts
+        .iter()
+        .cloned()
+        .partition(is_velocity_counter);
+    close_mint_policy(
+        &mint_key,
+        &ctx.accounts.mint_policy.to_account_info(),
+        &pages,
+        &destination,
+    )?;
+    close_velocity_accounts(
+        &mint_key,
+        &ctx.accounts.velocity_config.to_account_info(),
+        &counters,
+        &destination,
+    )?;
+
+    // The approve account is closed to `destination` on exit
+    close_program_account(
+        &ctx.accounts.extra_metas_account.to_account_info(),
+        &destination,
+    )
+}
+
+/// TRANSFER HOOK ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct InitializeHook<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// Transfer hook authority of the mint
+    pub authority: Signer<'info>,
+    #[account(
+        extensions::transfer_hook::authority = authority,
+        extensions::transfer_hook::program_id = crate::ID
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + size_of::<ApproveAccount>(),
+        seeds = [APPROVE_ACCOUNT_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub approve_account: Box<Account<'info, ApproveAccount>>,
+    /// CHECK: This account's data is a buffer of TLV data
+    #[account(
+        init,
+        payer = payer,
+        space = get_meta_list_size(approve_account.key()),
+        seeds = [META_LIST_ACCOUNT_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub extra_metas_account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Account order is fixed by spl-transfer-hook-interface's Execute instruction
+#[derive(Accounts)]
+pub struct TransferHookExecute<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: source token account owner or delegate
+    pub owner: UncheckedAccount<'info>,
+    /// CHECK: ExtraAccountMetaList account
+    #[account(seeds = [META_LIST_ACCOUNT_SEED, mint.key().as_ref()], bump)]
+    pub extra_metas_account: UncheckedAccount<'info>,
+    /// Read-only so transfers of the mint do not contend for a write lock on it
+    #[account(seeds = [APPROVE_ACCOUNT_SEED, mint.key().as_ref()], bump = approve_account.bump)]
+    pub approve_account: Account<'info, ApproveAccount>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTransferPolicy<'info> {
+    pub authority: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [APPROVE_ACCOUNT_SEED, mint.key().as_ref()],
+        bump = approve_account.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub approve_account: Account<'info, ApproveAccount>,
+}
+
+#[derive(Accounts)]
+pub struct CloseHookAccounts<'info> {
+    /// Close authority of the mint
+    pub authority: Signer<'info>,
+    /// CHECK: any account can receive the refunded rent
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    /// The mint being closed (supply must be zero)
+    #[account(
+        extensions::close_authority::authority = authority,
+        constraint = mint.supply == 0 @ TransferHookError::NonZeroSupply
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [APPROVE_ACCOUNT_SEED, mint.key().as_ref()],
+        bump = approve_account.bump,
+        has_one = mint,
+        close = destination
+    )]
+    pub approve_account: Box<Account<'info, ApproveAccount>>,
+    /// CHECK: extra-account-metas list of the mint, closed in the handler
+    #[account(mut, seeds = [META_LIST_ACCOUNT_SEED, mint.key().as_ref()], bump)]
+    pub extra_metas_account: UncheckedAccount<'info>,
+    /// CHECK: only initialized once the mint has a policy, closed in the handler when present
+    #[account(mut, seeds = [MINT_POLICY_SEED, mint.key().as_ref()], bump)]
+    pub mint_policy: UncheckedAccount<'info>,
+    /// CHECK: only initialized once the mint has velocity limits, closed in the handler when
+    /// present
+    #[account(mut, seeds = [VELOCITY_CONFIG_SEED, mint.key().as_ref()], bump)]
+    pub velocity_config: UncheckedAccount<'info>,
+}
+
+#[event]
+pub struct TransferPolicyUpdated {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub max_transfer_amount: u64,
+}
+
+#[error_code]
+pub enum TransferHookError {
+    #[msg("hook was not invoked during a token transfer")]
+    NotTransferring,
+    #[msg("transfer amount exceeds the mint's policy limit")]
+    TransferLimitExceeded,
+    #[msg("mint supply must be zero before it can be closed")]
+    NonZeroSupply,
+}
@@ -0,0 +1,512 @@
+use anchor_lang::{ prelude::*, Discriminator };
+use anchor_spl::token_interface::Mint;
+use std::mem::size_of;
+
+use crate::{ state::*, utils::* };
+
+/// VELOCITY LIMIT FUNCTIONS
+// Create the mint's velocity limits and add the config and per-owner counters to the
+// hook's meta list. Tier limits start unset (0 = no limit).
+pub fn initialize_velocity_config(
+    ctx: Context<InitializeVelocityConfig>,
+    window_seconds: i64,
+    default_limit: u64
+) -> Result<()> {
+    require!(window_seconds > 0, VelocityError::InvalidWindow);
+
+    let config = &mut ctx.accounts.velocity_config;
+    config.mint = ctx.accounts.mint.key();
+    config.authority = ctx.accounts.authority.key();
+    config.window_seconds = window_seconds;
+    config.default_limit = default_limit;
+    config.tier_limits = [0; MAX_VELOCITY_TIERS];
+    config.counter_count = 0;
+    config.bump = ctx.bumps.velocity_config;
+
+    write_policy_meta_list(
+        ctx.accounts.extra_metas_account.to_account_info(),
+        ctx.accounts.approve_account.key(),
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info()
+    )?;
+
+    emit!(VelocityLimitsUpdated {
+        mint: config.mint,
+        window_seconds,
+        default_limit,
+        tier_limits: config.tier_limits,
+    });
+    Ok(())
+}
+
+// Replace the window, the mint-wide limit and the tier limits (tiers not listed are unlimited)
+pub fn update_velocity_limits(
+    ctx: Context<UpdateVelocityConfig>,
+    window_seconds: i64,
+    default_limit: u64,
+    tier_limits: Vec<u64>
+) -> Result<()> {
+    require!(window_seconds > 0, VelocityError::InvalidWindow);
+    require!(tier_limits.len() <= MAX_VELOCITY_TIERS, VelocityError::TooManyTiers);
+
+    let config = &mut ctx.accounts.velocity_config;
+    config.window_seconds = window_seconds;
+    config.default_limit = default_limit;
+    config.tier_limits = [0; MAX_VELOCITY_TIERS];
+    config.tier_limits[..tier_limits.len()].copy_from_slice(&tier_limits);
+
+    emit!(VelocityLimitsUpdated {
+        mint: config.mint,
+        window_seconds,
+        default_limit,
+        tier_limits: config.tier_limits,
+    });
+    Ok(())
+}
+
+// Assign an owner's tier or exempt it (e.g. an AMM vault authority), creating its counter if needed
+pub fn update_wallet_velocity(
+    ctx: Context<SetWalletVelocity>,
+    tier: u8,
+    exempt: bool
+) -> Result<()> {
+    require!((tier as usize) <= MAX_VELOCITY_TIERS, VelocityError::InvalidTier);
+
+    let counter = &mut ctx.accounts.counter;
+    if counter.mint == Pubkey::default() {
+        counter.mint = ctx.accounts.mint.key();
+        counter.owner = ctx.accounts.owner.key();
+        counter.window_start = Clock::get()?.unix_timestamp;
+        counter.bump = ctx.bumps.counter;
+        count_counter(&mut ctx.accounts.velocity_config)?;
+    }
+    counter.tier = tier;
+    counter.exempt = exempt;
+
+    emit!(WalletVelocityUpdated {
+        mint: counter.mint,
+        owner: counter.owner,
+        tier,
+        exempt,
+    });
+    Ok(())
+}
+
+// Open the outflow counter an owner needs before sending a mint with a mint-wide limit (anyone can pay)
+pub fn create_velocity_counter(ctx: Context<OpenVelocityCounter>) -> Result<()> {
+    let counter = &mut ctx.accounts.counter;
+    counter.mint = ctx.accounts.mint.key();
+    counter.owner = ctx.accounts.owner.key();
+    counter.tier = 0;
+    counter.exempt = false;
+    counter.window_start = Clock::get()?.unix_timestamp;
+    counter.current_amount = 0;
+    counter.previous_amount = 0;
+    counter.bump = ctx.bumps.counter;
+    count_counter(&mut ctx.accounts.velocity_config)
+}
+
+// Track a newly opened counter so closing the mint can check every counter was closed
+fn count_counter(config: &mut VelocityConfig) -> Result<()> {
+    config.counter_count = config.counter_count
+        .checked_add(1)
+        .ok_or(VelocityError::MathOverflow)?;
+    Ok(())
+}
+
+// Close the velocity config and its owner counters along with the mint. Every counter opened
+// for the mint must be among `counters` so none is left holding rent.
+pub fn close_velocity_accounts<'info>(
+    mint: &Pubkey,
+    config_info: &AccountInfo<'info>,
+    counters: &[AccountInfo<'info>],
+    destination: &AccountInfo<'info>
+) -> Result<()> {
+    if config_info.owner != &crate::ID {
+        require!(counters.is_empty(), VelocityError::InvalidVelocityAccount);
+        return Ok(());
+    }
+    let config = VelocityConfig::try_deserialize(&mut &config_info.try_borrow_data()?[..])?;
+
+    for counter_info in counters {
+        require_keys_eq!(*counter_info.owner, crate::ID, VelocityError::InvalidVelocityAccount);
+        let counter = VelocityCounter::try_deserialize(&mut &counter_info.try_borrow_data()?[..])?;
+        let counter_key = Pubkey::create_program_address(
+            &[VELOCITY_COUNTER_SEED, mint.as_ref(), counter.owner.as_ref(), &[counter.bump]],
+            &crate::ID
+        ).map_err(|_| VelocityError::InvalidVelocityAccount)?;
+        require!(
+            counter.mint == *mint && counter_key == counter_info.key(),
+            VelocityError::InvalidVelocityAccount
+        );
+        close_program_account(counter_info, destination)?;
+    }
+    require!(counters.len() as u64 == config.counter_count, VelocityError::CountersMissing);
+
+    close_program_account(config_info, destination)
+}
+
+// Whether `account` holds a velocity counter (used to tell counters from blocklist pages)
+pub fn is_velocity_counter(account: &AccountInfo) -> bool {
+    account
+        .try_borrow_data()
+        .map(|data| data.starts_with(&VelocityCounter::DISCRIMINATOR))
+        .unwrap_or(false)
+}
+
+// Count `amount` against the source owner's rolling window inside the transfer hook.
+// `velocity_accounts` are the config and the source owner's counter; a mint without a
+// config passes none or an empty config. Owners without a counter are rejected whenever
+// the mint-wide limit is set, since their outflow could not be recorded.
+pub fn enforce_velocity_limit(
+    mint: &Pubkey,
+    source_owner: &Pubkey,
+    velocity_accounts: &[AccountInfo],
+    amount: u64
+) -> Result<()> {
+    let [config_info, counter_info, ..] = velocity_accounts else {
+        return Ok(());
+    };
+    if config_info.data_is_empty() {
+        return Ok(());
+    }
+
+    require_keys_eq!(*config_info.owner, crate::ID, VelocityError::InvalidVelocityAccount);
+    let config = VelocityConfig::try_deserialize(&mut &config_info.try_borrow_data()?[..])?;
+    require_keys_eq!(config.mint, *mint, VelocityError::InvalidVelocityAccount);
+
+    if counter_info.data_is_empty() {
+        require!(config.limit_for(0) == 0, VelocityError::CounterMissing);
+        return Ok(());
+    }
+    require_keys_eq!(*counter_info.owner, crate::ID, VelocityError::InvalidVelocityAccount);
+    let mut counter = VelocityCounter::try_deserialize(
+        &mut &counter_info.try_borrow_data()?[..]
+    )?;
+    require!(
+        counter.mint == *mint && counter.owner == *source_owner,
+        VelocityError::InvalidVelocityAccount
+    );
+    if counter.exempt {
+        return Ok(());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    roll_window(&mut counter, config.window_seconds, now);
+    counter.current_amount = counter.current_amount
+        .checked_add(amount)
+        .ok_or(VelocityError::MathOverflow)?;
+
+    let outflow = rolling_outflow(&counter, config.window_seconds, now)?;
+    require!(
+        within_limit(outflow, config.limit_for(counter.tier)),
+        VelocityError::VelocityLimitExceeded
+    );
+
+    counter.try_serialize(&mut &mut counter_info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+// Whether `outflow` fits under `limit` (0 = no limit)
+fn within_limit(outflow: u64, limit: u64) -> bool {
+    limit == 0 || outflow <= limit
+}
+
+// Advance the counter to the window containing `now`, shifting the current amount into the
+// previous slot (or dropping both when a whole window passed without transfers)
+fn roll_window(counter: &mut VelocityCounter, window_seconds: i64, now: i64) {
+    let elapsed = now.saturating_sub(counter.window_start);
+    if elapsed >= window_seconds.saturating_mul(2) {
+        counter.previous_amount = 0;
+        counter.current_amount = 0;
+        counter.window_start = now;
+    } else if elapsed >= window_seconds {
+        counter.previous_amount = counter.current_amount;
+        counter.current_amount = 0;
+        counter.window_start += window_seconds;
+    }
+}
+
+// Sliding-window estimate of the outflow over the last `window_seconds`: the current window
+// plus the share of the previous window that still overlaps it
+fn rolling_outflow(counter: &VelocityCounter, window_seconds: i64, now: i64) -> Result<u64> {
+    let elapsed = now.saturating_sub(counter.window_start);
+    let remaining = window_seconds.saturating_sub(elapsed).max(0) as u128;
+    let carried = ((counter.previous_amount as u128) * remaining) / (window_seconds as u128);
+    u64::try_from((counter.current_amount as u128) + carried).map_err(
+        |_| error!(VelocityError::MathOverflow)
+    )
+}
+
+/// VELOCITY LIMIT ACCOUNTS FUNCTIONS
+#[derive(Accounts)]
+pub struct InitializeVelocityConfig<'info> {
+    /// Transfer hook authority of the mint (pays for the config and the larger meta list)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [APPROVE_ACCOUNT_SEED, mint.key().as_ref()],
+        bump = approve_account.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub approve_account: Box<Account<'info, ApproveAccount>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [VELOCITY_CONFIG_SEED, mint.key().as_ref()],
+        bump,
+        space = 8 + size_of::<VelocityConfig>()
+    )]
+    pub velocity_config: Box<Account<'info, VelocityConfig>>,
+    /// CHECK: ExtraAccountMetaList account, rewritten with the policy accounts
+    #[account(mut, seeds = [META_LIST_ACCOUNT_SEED, mint.key().as_ref()], bump)]
+    pub extra_metas_account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVelocityConfig<'info> {
+    pub authority: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [VELOCITY_CONFIG_SEED, mint.key().as_ref()],
+        bump = velocity_config.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub velocity_config: Box<Account<'info, VelocityConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct SetWalletVelocity<'info> {
+    /// Velocity authority (pays for the counter when it does not exist yet)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [VELOCITY_CONFIG_SEED, mint.key().as_ref()],
+        bump = velocity_config.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub velocity_config: Box<Account<'info, VelocityConfig>>,
+    /// CHECK: owner of the token accounts being limited; only its key is stored
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [VELOCITY_COUNTER_SEED, mint.key().as_ref(), owner.key().as_ref()],
+        bump,
+        space = 8 + size_of::<VelocityCounter>()
+    )]
+    pub counter: Box<Account<'info, VelocityCounter>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenVelocityCounter<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [VELOCITY_CONFIG_SEED, mint.key().as_ref()],
+        bump = velocity_config.bump,
+        has_one = mint
+    )]
+    pub velocity_config: Box<Account<'info, VelocityConfig>>,
+    /// CHECK: owner of the token accounts being limited; only its key is stored
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [VELOCITY_COUNTER_SEED, mint.key().as_ref(), owner.key().as_ref()],
+        bump,
+        space = 8 + size_of::<VelocityCounter>()
+    )]
+    pub counter: Box<Account<'info, VelocityCounter>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct VelocityLimitsUpdated {
+    pub mint: Pubkey,
+    pub window_seconds: i64,
+    pub default_limit: u64,
+    pub tier_limits: [u64; MAX_VELOCITY_TIERS],
+}
+
+#[event]
+pub struct WalletVelocityUpdated {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub tier: u8,
+    pub exempt: bool,
+}
+
+#[error_code]
+pub enum VelocityError {
+    #[msg("window must be longer than zero seconds")]
+    InvalidWindow,
+    #[msg("too many velocity tiers")]
+    TooManyTiers,
+    #[msg("velocity tier does not exist")]
+    InvalidTier,
+    #[msg("source owner has no velocity counter")]
+    CounterMissing,
+    #[msg("transfer exceeds the owner's rolling outflow limit")]
+    VelocityLimitExceeded,
+    #[msg("velocity config or counter does not belong to this transfer")]
+    InvalidVelocityAccount,
+    #[msg("math overflow")]
+    MathOverflow,
+    #[msg("every velocity counter of the mint must be closed with its config")]
+    CountersMissing,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: i64 = 100;
+
+    fn counter(window_start: i64, current_amount: u64, previous_amount: u64) -> VelocityCounter {
+        VelocityCounter {
+            mint: Pubkey::default(),
+            owner: Pubkey::default(),
+            tier: 0,
+            exempt: false,
+            window_start,
+            current_amount,
+            previous_amount,
+            bump: 0,
+        }
+    }
+
+    fn config(default_limit: u64, tier_limits: &[u64]) -> VelocityConfig {
+        let mut config = VelocityConfig {
+            mint: Pubkey::default(),
+            authority: Pubkey::default(),
+            window_seconds: WINDOW,
+            default_limit,
+            tier_limits: [0; MAX_VELOCITY_TIERS],
+            counter_count: 0,
+            bump: 0,
+        };
+        config.tier_limits[..tier_limits.len()].copy_from_slice(tier_limits);
+        config
+    }
+
+    #[test]
+    fn roll_window_keeps_the_current_window() {
+        let mut c = counter(1_000, 40, 10);
+        roll_window(&mut c, WINDOW, 1_099);
+        assert_eq!((c.window_start, c.current_amount, c.previous_amount), (1_000, 40, 10));
+    }
+
+    #[test]
+    fn roll_window_rolls_over_at_the_exact_boundary() {
+        let mut c = counter(1_000, 40, 10);
+        roll_window(&mut c, WINDOW, 1_100);
+        assert_eq!((c.window_start, c.current_amount, c.previous_amount), (1_100, 0, 40));
+    }
+
+    #[test]
+    fn roll_window_keeps_window_alignment_mid_window() {
+        let mut c = counter(1_000, 40, 10);
+        roll_window(&mut c, WINDOW, 1_150);
+        assert_eq!((c.window_start, c.current_amount, c.previous_amount), (1_100, 0, 40));
+    }
+
+    #[test]
+    fn roll_window_drops_both_windows_after_two_idle_windows() {
+        let mut c = counter(1_000, 40, 10);
+        roll_window(&mut c, WINDOW, 1_200);
+        assert_eq!((c.window_start, c.current_amount, c.previous_amount), (1_200, 0, 0));
+
+        let mut c = counter(1_000, 40, 10);
+        roll_window(&mut c, WINDOW, 5_000);
+        assert_eq!((c.window_start, c.current_amount, c.previous_amount), (5_000, 0, 0));
+    }
+
+    #[test]
+    fn rolling_outflow_weights_the_previous_window() {
+        // At the start of the window the whole previous window still overlaps
+        assert_eq!(rolling_outflow(&counter(1_000, 5, 100), WINDOW, 1_000).unwrap(), 105);
+        // Half way through, half of it does
+        assert_eq!(rolling_outflow(&counter(1_000, 5, 100), WINDOW, 1_050).unwrap(), 55);
+        // At the boundary nothing of it is left
+        assert_eq!(rolling_outflow(&counter(1_000, 5, 100), WINDOW, 1_100).unwrap(), 5);
+    }
+
+    #[test]
+    fn rolling_outflow_after_a_rollover_carries_the_old_current_window() {
+        let mut c = counter(1_000, 80, 0);
+        roll_window(&mut c, WINDOW, 1_125);
+        c.current_amount += 10;
+        assert_eq!(rolling_outflow(&c, WINDOW, 1_125).unwrap(), 70);
+    }
+
+    #[test]
+    fn limit_for_selects_the_default_or_the_tier_limit() {
+        let config = config(10, &[100, 0, 300]);
+        assert_eq!(config.limit_for(0), 10);
+        assert_eq!(config.limit_for(1), 100);
+        assert_eq!(config.limit_for(2), 0);
+        assert_eq!(config.limit_for(3), 300);
+        assert_eq!(config.limit_for(MAX_VELOCITY_TIERS as u8), 0);
+    }
+
+    #[test]
+    fn within_limit_treats_zero_as_unlimited() {
+        assert!(within_limit(u64::MAX, 0));
+        assert!(within_limit(10, 10));
+        assert!(!within_limit(11, 10));
+    }
+
+    #[test]
+    fn missing_counter_is_rejected_only_under_a_limit() {
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let config_key = Pubkey::new_unique();
+        let counter_key = Pubkey::new_unique();
+        let system = Pubkey::default();
+
+        let check = |default_limit: u64| {
+            let mut data = Vec::new();
+            VelocityConfig { mint, ..config(default_limit, &[]) }.try_serialize(&mut data).unwrap();
+            let (mut config_lamports, mut counter_lamports) = (1, 0);
+            let mut counter_data = [];
+            let accounts = [
+                AccountInfo::new(
+                    &config_key,
+                    false,
+                    false,
+                    &mut config_lamports,
+                    &mut data,
+                    &crate::ID,
+                    false,
+                    0
+                ),
+                AccountInfo::new(
+                    &counter_key,
+                    false,
+                    true,
+                    &mut counter_lamports,
+                    &mut counter_data,
+                    &system,
+                    false,
+                    0
+                ),
+            ];
+            enforce_velocity_limit(&mint, &owner, &accounts, 1)
+        };
+
+        assert!(check(0).is_ok());
+        assert_eq!(check(10).unwrap_err(), VelocityError::CounterMissing.into());
+    }
+}
@@ -0,0 +1,115 @@
+#![allow(unexpected_cfgs)]
+
+use anchor_lang::prelude::*;
+use spl_transfer_hook_interface::instruction::TransferHookInstruction;
+
+// Program ID for the transfer hook of spl program mints
+declare_id!("BTHMQqp22RzFimuNydEbebbgMnVERJiVxdsFbmZDNg8W");
+
+pub mod instructions;
+pub use instructions::*;
+pub mod utils;
+pub use utils::*;
+pub mod state;
+pub use state::*;
+
+#[program]
+pub mod spl_hook {
+    use super::*;
+
+    // TRANSFER HOOK FUNCTIONS
+
+    // Create a hooked mint's approve account and meta list (called by the spl program)
+    pub fn initialize_hook(ctx: Context<InitializeHook>) -> Result<()> {
+        instructions::initialize_hook_accounts(ctx)
+    }
+
+    // Close a hooked mint's approve account, meta list, policy and velocity accounts (called by
+    // the spl program's close_mint)
+    pub fn close_hook<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseHookAccounts<'info>>
+    ) -> Result<()> {
+        instructions::close_hook_accounts(ctx)
+    }
+
+    // Validate a transfer of a hooked mint (reached through `fallback`)
+    pub fn execute(ctx: Context<TransferHookExecute>, amount: u64) -> Result<()> {
+        instructions::execute_transfer_hook(ctx, amount)
+    }
+
+    // Update the per-transfer policy of a hooked mint
+    pub fn update_transfer_policy(
+        ctx: Context<UpdateTransferPolicy>,
+        max_transfer_amount: u64
+    ) -> Result<()> {
+        instructions::set_transfer_policy(ctx, max_transfer_amount)
+    }
+
+    // Create a mint's pause/blocklist policy and add it to the hook's extra accounts
+    pub fn initialize_policy(ctx: Context<InitializeMintPolicy>) -> Result<()> {
+        instructions::initialize_mint_policy(ctx)
+    }
+
+    // Halt or resume every transfer of a policy mint
+    pub fn set_paused(ctx: Context<UpdateMintPolicy>, paused: bool) -> Result<()> {
+        instructions::set_transfers_paused(ctx, paused)
+    }
+
+    // Block a wallet from sending or receiving a policy mint
+    pub fn block_wallet(ctx: Context<AddToBlocklist>) -> Result<()> {
+        instructions::add_to_blocklist(ctx)
+    }
+
+    // Remove a wallet from a policy mint's blocklist
+    pub fn unblock_wallet(ctx: Context<RemoveFromBlocklist>) -> Result<()> {
+        instructions::remove_from_blocklist(ctx)
+    }
+
+    // Create a mint's rolling-window outflow limits and add them to the hook's extra accounts
+    pub fn create_velocity_config(
+        ctx: Context<InitializeVelocityConfig>,
+        window_seconds: i64,
+        default_limit: u64
+    ) -> Result<()> {
+        instructions::initialize_velocity_config(ctx, window_seconds, default_limit)
+    }
+
+    // Change the window, the mint-wide limit and the tier limits
+    pub fn set_velocity_limits(
+        ctx: Context<UpdateVelocityConfig>,
+        window_seconds: i64,
+        default_limit: u64,
+        tier_limits: Vec<u64>
+    ) -> Result<()> {
+        instructions::update_velocity_limits(ctx, window_seconds, default_limit, tier_limits)
+    }
+
+    // Assign an owner's velocity tier or exempt it from the limits
+    pub fn set_wallet_velocity(
+        ctx: Context<SetWalletVelocity>,
+        tier: u8,
+        exempt: bool
+    ) -> Result<()> {
+        instructions::update_wallet_velocity(ctx, tier, exempt)
+    }
+
+    // Open an owner's outflow counter so it can send a mint with a mint-wide limit
+    pub fn open_velocity_counter(ctx: Context<OpenVelocityCounter>) -> Result<()> {
+        instructions::create_velocity_counter(ctx)
+    }
+
+    // Route spl-transfer-hook-interface `Execute` calls to the anchor handler
+    pub fn fallback<'info>(
+        program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        data: &[u8]
+    ) -> Result<()> {
+        match TransferHookInstruction::unpack(data)? {
+            TransferHookInstruction::Execute { amount } => {
+                let amount_bytes = amount.to_le_bytes();
+                __private::__global::execute(program_id, accounts, &amount_bytes)
+            }
+            _ => Err(ProgramError::InvalidInstructionData.into()),
+        }
+    }
+}
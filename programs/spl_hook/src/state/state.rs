@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+
+// Seed for the transfer hook approve account PDA
+pub const APPROVE_ACCOUNT_SEED: &[u8] = b"approve-account";
+// Seed for the extra-account-metas list PDA (fixed by spl-transfer-hook-interface)
+pub const META_LIST_ACCOUNT_SEED: &[u8] = b"extra-account-metas";
+// Seed for per-mint transfer policy PDA (pause flag)
+pub const MINT_POLICY_SEED: &[u8] = b"mint_policy";
+// Seed for blocklist page PDAs (one page per first byte of the blocked owner)
+pub const BLOCKLIST_SEED: &[u8] = b"blocklist";
+// Seed for per-mint transfer velocity limits PDA
+pub const VELOCITY_CONFIG_SEED: &[u8] = b"velocity_config";
+// Seed for per-owner rolling outflow counter PDA
+pub const VELOCITY_COUNTER_SEED: &[u8] = b"velocity_counter";
+
+// Maximum number of velocity tiers per mint (tier 0 is the mint-wide limit)
+pub const MAX_VELOCITY_TIERS: usize = 8;
+
+/// TRANSFER HOOK APPROVE ACCOUNT STATE
+#[account]
+pub struct ApproveAccount {
+    /// Mint whose transfers this account validates
+    pub mint: Pubkey,
+
+    /// Authority allowed to change the transfer policy
+    pub authority: Pubkey,
+
+    /// Largest amount allowed in a single transfer (0 = no limit)
+    pub max_transfer_amount: u64,
+
+    /// PDA bump for the approve account
+    pub bump: u8,
+}
+
+/// MINT POLICY STATE
+#[account]
+pub struct MintPolicy {
+    /// Mint whose transfers this policy governs
+    pub mint: Pubkey,
+
+    /// Admin allowed to pause transfers and edit the blocklist
+    pub authority: Pubkey,
+
+    /// Whether every transfer of the mint is rejected by the hook
+    pub paused: bool,
+
+    /// Number of owners currently blocked across all pages
+    pub blocked_count: u64,
+
+    /// PDA bump for the policy
+    pub bump: u8,
+}
+
+/// Blocked token-account owners whose key starts with `page`
+#[account]
+pub struct BlocklistPage {
+    /// Mint the blocklist applies to
+    pub mint: Pubkey,
+
+    /// First byte shared by every owner on this page
+    pub page: u8,
+
+    /// Owners whose source and destination accounts are rejected by the hook
+    pub owners: Vec<Pubkey>,
+
+    /// PDA bump for the page
+    pub bump: u8,
+}
+
+impl BlocklistPage {
+    // Account size (including discriminator) for a page holding `owners` entries
+    pub fn space(owners: usize) -> usize {
+        8 + 32 + 1 + 4 + owners * 32 + 1
+    }
+
+    // Page an owner is stored on (the hook derives the same page from token account data)
+    pub fn page_of(owner: &Pubkey) -> u8 {
+        owner.to_bytes()[0]
+    }
+}
+
+/// VELOCITY LIMIT STATE
+#[account]
+pub struct VelocityConfig {
+    /// Mint whose outflows are limited
+    pub mint: Pubkey,
+
+    /// Admin allowed to change limits, tiers and exemptions
+    pub authority: Pubkey,
+
+    /// Length of the rolling window in seconds
+    pub window_seconds: i64,
+
+    /// Mint-wide outflow limit per window for owners on tier 0 (0 = no limit)
+    pub default_limit: u64,
+
+    /// Outflow limit per window for tiers 1..=MAX_VELOCITY_TIERS (0 = no limit)
+    pub tier_limits: [u64; MAX_VELOCITY_TIERS],
+
+    /// Number of owner counters opened for the mint (all are closed with the config)
+    pub counter_count: u64,
+
+    /// PDA bump for the config
+    pub bump: u8,
+}
+
+impl VelocityConfig {
+    // Outflow limit of `tier` (0 = no limit)
+    pub fn limit_for(&self, tier: u8) -> u64 {
+        match tier {
+            0 => self.default_limit,
+            tier => self.tier_limits[(tier as usize) - 1],
+        }
+    }
+}
+
+/// Outflow of one owner's token accounts within the rolling window
+#[account]
+pub struct VelocityCounter {
+    /// Mint the counter tracks
+    pub mint: Pubkey,
+
+    /// Owner whose outgoing transfers are counted
+    pub owner: Pubkey,
+
+    /// Tier assigned by the admin (0 = mint-wide limit)
+    pub tier: u8,
+
+    /// Exempt owners (e.g. AMM vault authorities) are never limited
+    pub exempt: bool,
+
+    /// Start of the current window
+    pub window_start: i64,
+
+    /// Amount transferred in the current window
+    pub current_amount: u64,
+
+    /// Amount transferred in the window before it, weighted down as the current one elapses
+    pub previous_amount: u64,
+
+    /// PDA bump for the counter
+    pub bump: u8,
+}
@@ -0,0 +1,152 @@
+use anchor_lang::{
+    prelude::Result,
+    solana_program::{
+        account_info::AccountInfo, program::invoke, pubkey::Pubkey, rent::Rent,
+        system_instruction::transfer, system_program, sysvar::Sysvar,
+    },
+    Lamports,
+};
+use anchor_spl::token_interface::spl_token_2022::{
+    extension::{transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions},
+    state::Account,
+};
+use spl_tlv_account_resolution::{
+    account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
+};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+use crate::state::{BLOCKLIST_SEED, MINT_POLICY_SEED, VELOCITY_CONFIG_SEED, VELOCITY_COUNTER_SEED};
+
+// Top up `account` to rent exemption from `payer`; also used by the main program, which
+// depends on this crate rather than keeping its own copy
+pub fn update_account_lamports_to_minimum_balance<'info>(
+    account: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Result<()> {
+    // Saturate so accounts that shrank (or are already funded) are left alone
+    let extra_lamports = Rent::get()?
+        .minimum_balance(account.data_len())
+        .saturating_sub(account.get_lamports());
+    if extra_lamports > 0 {
+        invoke(
+            &transfer(payer.key, account.key, extra_lamports),
+            &[payer, account, system_program],
+        )?;
+    }
+    Ok(())
+}
+
+// Close a program-owned account, moving all of its lamports to `destination`
+pub fn close_program_account<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    destination.add_lamports(account.get_lamports())?;
+    account.sub_lamports(account.get_lamports())?;
+    account.assign(&system_program::ID);
+    account.realloc(0, false)?;
+    Ok(())
+}
+
+// Meta list every hooked mint starts with: just the approve account
+pub fn get_meta_list(approve_account: Pubkey) -> Vec<ExtraAccountMeta> {
+    vec![ExtraAccountMeta {
+        discriminator: 0,
+        address_config: approve_account.to_bytes(),
+        is_signer: false.into(),
+        is_writable: false.into(),
+    }]
+}
+
+pub fn get_meta_list_size(approve_account: Pubkey) -> usize {
+    // safe because the list holds a single entry
+    ExtraAccountMetaList::size_of(get_meta_list(approve_account).len()).unwrap()
+}
+
+// Meta list of a mint with a policy account: the approve account, the policy PDA, the
+// blocklist pages of the source and destination owners (derived from the first byte of
+// each token account's owner field), the velocity config and the source owner's counter
+pub fn get_policy_meta_list(approve_account: Pubkey) -> Result<Vec<ExtraAccountMeta>> {
+    let mut metas = get_meta_list(approve_account);
+    metas.push(ExtraAccountMeta::new_with_seeds(
+        &[
+            Seed::Literal {
+                bytes: MINT_POLICY_SEED.to_vec(),
+            },
+            Seed::AccountKey { index: 1 },
+        ],
+        false,
+        false,
+    )?);
+    // Source token account is index 0, destination token account is index 2
+    for token_account_index in [0, 2] {
+        metas.push(ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: BLOCKLIST_SEED.to_vec(),
+                },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountData {
+                    account_index: token_account_index,
+                    data_index: 32,
+                    length: 1,
+                },
+            ],
+            false,
+            false,
+        )?);
+    }
+    metas.push(ExtraAccountMeta::new_with_seeds(
+        &[
+            Seed::Literal {
+                bytes: VELOCITY_CONFIG_SEED.to_vec(),
+            },
+            Seed::AccountKey { index: 1 },
+        ],
+        false,
+        false,
+    )?);
+    metas.push(ExtraAccountMeta::new_with_seeds(
+        &[
+            Seed::Literal {
+                bytes: VELOCITY_COUNTER_SEED.to_vec(),
+            },
+            Seed::AccountKey { index: 1 },
+            Seed::AccountData {
+                account_index: 0,
+                data_index: 32,
+                length: 32,
+            },
+        ],
+        false,
+        true,
+    )?);
+    Ok(metas)
+}
+
+// Rewrite the hook's meta list with the policy accounts, growing it (and topping up rent) first.
+// Running it again is a no-op, so every policy feature can call it when it is set up.
+pub fn write_policy_meta_list<'info>(
+    extra_metas_account: AccountInfo<'info>,
+    approve_account: Pubkey,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Result<()> {
+    let metas = get_policy_meta_list(approve_account)?;
+    extra_metas_account.realloc(ExtraAccountMetaList::size_of(metas.len())?, false)?;
+    update_account_lamports_to_minimum_balance(extra_metas_account.clone(), payer, system_program)?;
+    ExtraAccountMetaList::update::<ExecuteInstruction>(
+        &mut extra_metas_account.try_borrow_mut_data()?,
+        &metas,
+    )?;
+    Ok(())
+}
+
+// Whether the token program is currently transferring out of this token account
+pub fn is_transferring(account: &AccountInfo) -> Result<bool> {
+    let account_data = account.data.borrow();
+    let account_with_extension = StateWithExtensions::<Account>::unpack(&account_data)?;
+    let extension_data = account_with_extension.get_extension::<TransferHookAccount>()?;
+    Ok(bool::from(extension_data.transferring))
+}
@@ -6,6 +6,9 @@ import {
   getAssociatedTokenAddressSync,
   createAssociatedTokenAccount,
   createAssociatedTokenAccountIdempotent,
+  createMint,
+  mintTo,
+  TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import {
   PublicKey,
@@ -31,6 +34,10 @@ const payer = (provider.wallet as NodeWallet).payer;
 const TOKEN_2022_ID = new PublicKey(
   "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
 );
+// Transfer hook program ID
+const HOOK_PROGRAM_ID = new PublicKey(
+  "BTHMQqp22RzFimuNydEbebbgMnVERJiVxdsFbmZDNg8W"
+);
 
 // Seeds for PDAs
 const LIQUIDITY_POOL = Buffer.from("liquidity_pool");
@@ -50,9 +57,45 @@ const bal = async (pk: PublicKey) =>
 const extraPda = (mint: PublicKey) =>
   PublicKey.findProgramAddressSync(
     [Buffer.from("extra-account-metas"), mint.toBuffer()],
-    program.programId
+    HOOK_PROGRAM_ID
+  )[0];
+
+// Helper function to get transfer-hook approve account PDA
+const approvePda = (mint: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("approve-account"), mint.toBuffer()],
+    HOOK_PROGRAM_ID
   )[0];
 
+// Helper function to list the accounts Token-2022 needs to invoke the hook of `mint`
+const hookMetas = (mint: PublicKey) =>
+  [extraPda(mint), approvePda(mint), HOOK_PROGRAM_ID].map((pubkey) => ({
+    pubkey,
+    isSigner: false,
+    isWritable: false,
+  }));
+
+// Register `admin` as an unlimited minter for `mint`
+const addMinter = (admin: Keypair, mint: PublicKey) =>
+  program.methods
+    .addMinter(new BN("18446744073709551615"), new BN(0), true)
+    .accountsStrict({
+      authority: admin.publicKey,
+      mint,
+      mintConfig: PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_config"), mint.toBuffer()],
+        program.programId
+      )[0],
+      minterAuthority: admin.publicKey,
+      minter: PublicKey.findProgramAddressSync(
+        [Buffer.from("minter"), mint.toBuffer(), admin.publicKey.toBuffer()],
+        program.programId
+      )[0],
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([admin])
+    .rpc();
+
 const AIRDROP_AMOUNT_SOL = 2;
 let mintA: PublicKey,
   mintB: PublicKey,
@@ -93,23 +136,31 @@ describe("Amm_Program", () => {
 
     // Derive mint PDAs for both tokens
     [mintA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("mint"), payer.publicKey.toBuffer()],
+      [
+        Buffer.from("mint"),
+        payer.publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
       program.programId
     );
 
     [mintB] = PublicKey.findProgramAddressSync(
-      [Buffer.from("mint"), other.publicKey.toBuffer()],
+      [
+        Buffer.from("mint"),
+        other.publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
       program.programId
     );
     // Derive extra metas PDAs
     [xMetasA] = PublicKey.findProgramAddressSync(
       [Buffer.from("extra-account-metas"), mintA.toBuffer()],
-      program.programId
+      HOOK_PROGRAM_ID
     );
 
     [xMetasB] = PublicKey.findProgramAddressSync(
       [Buffer.from("extra-account-metas"), mintB.toBuffer()],
-      program.programId
+      HOOK_PROGRAM_ID
     );
 
     // Derive associated token accounts
@@ -137,16 +188,43 @@ describe("Amm_Program", () => {
         decimals_0,
         TOKEN_NAME_0,
         TOKEN_SYMBOL_0,
-        METADATA_URI_0
+        METADATA_URI_0,
+        {
+          transferHook: false,
+          transferFee: null,
+          interestRate: null,
+          nonTransferable: false,
+          permanentDelegate: null,
+          defaultFrozen: false,
+          confidentialTransfer: null,
+          groupPointer: false,
+          groupMemberPointer: false,
+        },
+        new BN(0),
+        null
       )
       .accountsStrict({
         payer: payer.publicKey,
         authority: payer.publicKey,
         receiver: payer.publicKey,
         mint: mintA,
+        mintRegistry: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_registry"), payer.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mintA.toBuffer()],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mintA.toBuffer()],
+          program.programId
+        )[0],
         mintTokenAccount: userA,
+        approveAccount: approvePda(mintA),
         extraMetasAccount: xMetasA,
         systemProgram: anchor.web3.SystemProgram.programId,
+        hookProgram: HOOK_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
         tokenProgram: TOKEN_2022_ID,
       })
@@ -167,16 +245,44 @@ describe("Amm_Program", () => {
         decimals_1,
         TOKEN_NAME_1,
         TOKEN_SYMBOL_1,
-        METADATA_URI_1
+        METADATA_URI_1,
+        {
+          // Token B is hooked so every AMM transfer of it runs the hook
+          transferHook: true,
+          transferFee: null,
+          interestRate: null,
+          nonTransferable: false,
+          permanentDelegate: null,
+          defaultFrozen: false,
+          confidentialTransfer: null,
+          groupPointer: false,
+          groupMemberPointer: false,
+        },
+        new BN(0),
+        null
       )
       .accountsStrict({
         payer: other.publicKey,
         authority: other.publicKey,
         receiver: other.publicKey,
         mint: mintB,
+        mintRegistry: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_registry"), other.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mintB.toBuffer()],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mintB.toBuffer()],
+          program.programId
+        )[0],
         mintTokenAccount: userB,
+        approveAccount: approvePda(mintB),
         extraMetasAccount: xMetasB,
         systemProgram: anchor.web3.SystemProgram.programId,
+        hookProgram: HOOK_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
         tokenProgram: TOKEN_2022_ID,
       })
@@ -199,12 +305,25 @@ describe("Amm_Program", () => {
     const mintAmount = new BN(1_000 * 10 ** decimals_0);
 
     // Mint tokens to first user
+    await addMinter(payer, mintA);
     await program.methods
       .mintTokens(mintAmount)
       .accountsStrict({
         mint: mintA,
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mintA.toBuffer()],
+          program.programId
+        )[0],
         to: userA,
         authority: payer.publicKey,
+        minter: PublicKey.findProgramAddressSync(
+          [Buffer.from("minter"), mintA.toBuffer(), payer.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mintA.toBuffer()],
+          program.programId
+        )[0],
         tokenProgram: TOKEN_2022_ID,
       })
       .signers([payer])
@@ -238,12 +357,25 @@ describe("Amm_Program", () => {
     );
 
     // Mint tokens to payer's token B account
+    await addMinter(other, mintB);
     await program.methods
       .mintTokens(mintAmount)
       .accountsStrict({
         mint: mintB,
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mintB.toBuffer()],
+          program.programId
+        )[0],
         to: payerBata,
         authority: other.publicKey,
+        minter: PublicKey.findProgramAddressSync(
+          [Buffer.from("minter"), mintB.toBuffer(), other.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mintB.toBuffer()],
+          program.programId
+        )[0],
         tokenProgram: TOKEN_2022_ID,
       })
       .signers([other])
@@ -316,6 +448,8 @@ describe("Amm_Program", () => {
         pool: poolPda,
         lpMint: lpMint,
         systemProgram: SystemProgram.programId,
+        tokenAProgram: TOKEN_2022_ID,
+        tokenBProgram: TOKEN_2022_ID,
         tokenProgram: TOKEN_2022_ID,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       })
@@ -377,9 +511,12 @@ describe("Amm_Program", () => {
         userTokenBAccount: payerBata,
         userLpMintAccount: userLp,
         systemProgram: SystemProgram.programId,
+        tokenAProgram: TOKEN_2022_ID,
+        tokenBProgram: TOKEN_2022_ID,
         tokenProgram: TOKEN_2022_ID,
         associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
       })
+      .remainingAccounts(hookMetas(mintB))
       .signers([payer])
       .rpc();
 
@@ -441,8 +578,10 @@ describe("Amm_Program", () => {
         userOut: userB,
         tokenAMint: mintA,
         tokenBMint: mintB,
-        tokenProgram: TOKEN_2022_ID,
+        tokenAProgram: TOKEN_2022_ID,
+        tokenBProgram: TOKEN_2022_ID,
       })
+      .remainingAccounts(hookMetas(mintB))
       .rpc();
 
     // Get balances after swap
@@ -507,8 +646,11 @@ describe("Amm_Program", () => {
         userTokenBAccount: userB,
         lpMint: lpMint,
         userLpMintAccount: userLp,
+        tokenAProgram: TOKEN_2022_ID,
+        tokenBProgram: TOKEN_2022_ID,
         tokenProgram: TOKEN_2022_ID,
       })
+      .remainingAccounts(hookMetas(mintB))
       .rpc();
 
     // Get balances after removing liquidity
@@ -546,6 +688,142 @@ describe("Amm_Program", () => {
       new anchor.BN(liqudity_before.toString()).sub(remove_liquidity_amount)
     );
   });
+
+  it("Pairs a classic SPL Token mint with a Token-2022 mint", async () => {
+    // Classic mint standing in for USDC / wrapped SOL
+    const classicMint = await createMint(
+      provider.connection,
+      payer,
+      payer.publicKey,
+      null,
+      6,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    const payerClassic = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      payer,
+      classicMint,
+      payer.publicKey,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      payer,
+      classicMint,
+      payerClassic,
+      payer,
+      1_000_000_000,
+      [],
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+
+    const [mixedPool] = PublicKey.findProgramAddressSync(
+      [LIQUIDITY_POOL, mintA.toBuffer(), classicMint.toBuffer()],
+      program.programId
+    );
+    // Each vault lives under its own mint's token program
+    const mixedVaultA = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      payer,
+      mintA,
+      mixedPool,
+      undefined,
+      TOKEN_2022_ID,
+      ASSOCIATED_PROGRAM_ID,
+      true
+    );
+    const mixedVaultB = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      payer,
+      classicMint,
+      mixedPool,
+      undefined,
+      TOKEN_PROGRAM_ID,
+      ASSOCIATED_PROGRAM_ID,
+      true
+    );
+    const [mixedLpMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), mixedPool.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .initializeLiquidityPoolAmm("PBC/CLASSIC", 30)
+      .accountsStrict({
+        admin: payer.publicKey,
+        tokenAMint: mintA,
+        tokenBMint: classicMint,
+        vaultTokenA: mixedVaultA,
+        vaultTokenB: mixedVaultB,
+        pool: mixedPool,
+        lpMint: mixedLpMint,
+        systemProgram: SystemProgram.programId,
+        tokenAProgram: TOKEN_2022_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([payer])
+      .rpc();
+
+    const mixedUserLp = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      payer,
+      mixedLpMint,
+      payer.publicKey,
+      undefined,
+      TOKEN_2022_ID
+    );
+    await program.methods
+      .addLiquidityAmm(new anchor.BN(10).mul(PRECISION), new BN(50_000_000))
+      .accountsStrict({
+        depositor: payer.publicKey,
+        tokenAMint: mintA,
+        tokenBMint: classicMint,
+        vaultA: mixedVaultA,
+        vaultB: mixedVaultB,
+        pool: mixedPool,
+        lpMint: mixedLpMint,
+        userTokenAAccount: userA,
+        userTokenBAccount: payerClassic,
+        userLpMintAccount: mixedUserLp,
+        systemProgram: SystemProgram.programId,
+        tokenAProgram: TOKEN_2022_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([payer])
+      .rpc();
+
+    const classicBefore = (
+      await getAccount(provider.connection, payerClassic, undefined, TOKEN_PROGRAM_ID)
+    ).amount;
+    await program.methods
+      .swapAmm(new anchor.BN(1).mul(PRECISION), new BN(1))
+      .accountsStrict({
+        swapper: payer.publicKey,
+        pool: mixedPool,
+        vaultIn: mixedVaultA,
+        vaultOut: mixedVaultB,
+        userIn: userA,
+        userOut: payerClassic,
+        tokenAMint: mintA,
+        tokenBMint: classicMint,
+        tokenAProgram: TOKEN_2022_ID,
+        tokenBProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const classicAfter = (
+      await getAccount(provider.connection, payerClassic, undefined, TOKEN_PROGRAM_ID)
+    ).amount;
+    expect(classicAfter > classicBefore).to.eql(true);
+  });
 });
 
 // Helper function to derive associated token address
@@ -1,17 +1,54 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
-import { PublicKey, Keypair } from "@solana/web3.js";
+import {
+  PublicKey,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  Transaction,
+  TransactionInstruction,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
 import { Spl } from "../target/types/spl";
+import { SplHook } from "../target/types/spl_hook";
 import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
 import NodeWallet from "@coral-xyz/anchor/dist/cjs/nodewallet";
-import { getAccount } from "@solana/spl-token";
+import {
+  getAccount,
+  createAssociatedTokenAccountIdempotent,
+  createTransferCheckedWithTransferHookInstruction,
+  transferCheckedWithFee,
+  getTokenMetadata,
+  getMint,
+  getExtensionTypes,
+  getTokenGroupState,
+  getTokenGroupMemberState,
+  ExtensionType,
+  createEnableCpiGuardInstruction,
+  createDisableCpiGuardInstruction,
+  getCpiGuard,
+  getMemoTransfer,
+  createTransferCheckedInstruction,
+  addExtraAccountMetasForExecute,
+  createMint,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
 import { expect, assert } from "chai";
+import { execFileSync } from "child_process";
+import { readFileSync } from "fs";
+import { join } from "path";
 
 // Token-2022 program ID
 const TOKEN_2022_PROGRAM_ID = new anchor.web3.PublicKey(
   "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
 );
 
+// Transfer hook program ID (owns the approve account, meta list, policy and velocity PDAs)
+const HOOK_PROGRAM_ID = new anchor.web3.PublicKey(
+  "BTHMQqp22RzFimuNydEbebbgMnVERJiVxdsFbmZDNg8W"
+);
+
 // Helper function to derive associated token address
 function associatedAddress({
   mint,
@@ -26,6 +63,215 @@ function associatedAddress({
   )[0];
 }
 
+// Helper function to create a mint PDA owned by `authority` with extensions
+async function createMintWithExtensions(
+  program: Program<Spl>,
+  authority: Keypair,
+  decimals: number,
+  extensions: Record<string, unknown>,
+  nonce = 0,
+  maxSupply: BN | null = null
+): Promise<{ mint: PublicKey; ata: PublicKey }> {
+  const [mint] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("mint"),
+      authority.publicKey.toBuffer(),
+      new BN(nonce).toArrayLike(Buffer, "le", 8),
+    ],
+    program.programId
+  );
+  const ata = associatedAddress({ mint, owner: authority.publicKey });
+  await program.methods
+    .createMintAccount(decimals, "Extension Token", "EXT", "", {
+      transferHook: false,
+      transferFee: null,
+      interestRate: null,
+      nonTransferable: false,
+      permanentDelegate: null,
+      defaultFrozen: false,
+      confidentialTransfer: null,
+      groupPointer: false,
+      groupMemberPointer: false,
+      ...extensions,
+    } as any, new BN(nonce), maxSupply)
+    .accountsStrict({
+      payer: authority.publicKey,
+      authority: authority.publicKey,
+      receiver: authority.publicKey,
+      mint,
+      mintRegistry: PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_registry"), authority.publicKey.toBuffer()],
+        program.programId
+      )[0],
+      mintConfig: PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_config"), mint.toBuffer()],
+        program.programId
+      )[0],
+      mintAuthority: PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_authority"), mint.toBuffer()],
+        program.programId
+      )[0],
+      mintTokenAccount: ata,
+      approveAccount: PublicKey.findProgramAddressSync(
+        [Buffer.from("approve-account"), mint.toBuffer()],
+        HOOK_PROGRAM_ID
+      )[0],
+      extraMetasAccount: PublicKey.findProgramAddressSync(
+        [Buffer.from("extra-account-metas"), mint.toBuffer()],
+        HOOK_PROGRAM_ID
+      )[0],
+      systemProgram: anchor.web3.SystemProgram.programId,
+      hookProgram: HOOK_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .signers([authority])
+    .rpc();
+
+  // Let the creator mint directly in tests
+  await addMinter(program, authority, mint);
+  return { mint, ata };
+}
+
+// Helper function to fund a fresh keypair
+async function airdrop(
+  provider: anchor.AnchorProvider,
+  to: PublicKey,
+  sol = 2
+): Promise<void> {
+  const sig = await provider.connection.requestAirdrop(
+    to,
+    sol * LAMPORTS_PER_SOL
+  );
+  await provider.connection.confirmTransaction(sig, "confirmed");
+}
+
+// Helper function to register `minter` for `mint` (unlimited unless limits are given)
+async function addMinter(
+  program: Program<Spl>,
+  admin: Keypair,
+  mint: PublicKey,
+  minter: PublicKey = admin.publicKey,
+  allowance: BN = new BN("18446744073709551615"),
+  epochLimit: BN = new BN(0),
+  enabled = true
+): Promise<PublicKey> {
+  const [minterPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("minter"), mint.toBuffer(), minter.toBuffer()],
+    program.programId
+  );
+  await program.methods
+    .addMinter(allowance, epochLimit, enabled)
+    .accountsStrict({
+      authority: admin.publicKey,
+      mint,
+      mintConfig: PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_config"), mint.toBuffer()],
+        program.programId
+      )[0],
+      minterAuthority: minter,
+      minter: minterPda,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([admin])
+    .rpc();
+  return minterPda;
+}
+
+// Helper function to mint through `minter`'s registry entry
+async function mintTokensTo(
+  program: Program<Spl>,
+  mint: PublicKey,
+  minter: Keypair,
+  to: PublicKey,
+  amount: BN
+): Promise<void> {
+  await program.methods
+    .mintTokens(amount)
+    .accountsStrict({
+      mint,
+      mintConfig: PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_config"), mint.toBuffer()],
+        program.programId
+      )[0],
+      to,
+      authority: minter.publicKey,
+      minter: PublicKey.findProgramAddressSync(
+        [Buffer.from("minter"), mint.toBuffer(), minter.publicKey.toBuffer()],
+        program.programId
+      )[0],
+      mintAuthority: PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_authority"), mint.toBuffer()],
+        program.programId
+      )[0],
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .signers([minter])
+    .rpc();
+}
+
+// Helper function to toggle CPI guard on a holder's account. Token-2022 rejects the toggle inside a
+// CPI, so the program only makes room for the extension and the toggle follows as a top-level
+// Token-2022 instruction in the same transaction
+async function setCpiGuard(
+  program: Program<Spl>,
+  owner: Keypair,
+  tokenAccount: PublicKey,
+  enabled: boolean
+): Promise<string> {
+  const reallocate = await program.methods
+    .reallocateTokenAccount([{ cpiGuard: {} }])
+    .accountsStrict({
+      owner: owner.publicKey,
+      tokenAccount,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .instruction();
+  const toggle = (
+    enabled ? createEnableCpiGuardInstruction : createDisableCpiGuardInstruction
+  )(tokenAccount, owner.publicKey, [], TOKEN_2022_PROGRAM_ID);
+  return sendAndConfirmTransaction(
+    program.provider.connection,
+    new Transaction().add(reallocate, toggle),
+    [owner]
+  );
+}
+
+// Helper function to resolve the transfer-hook accounts a program wrapper forwards to Token-2022
+async function hookAccounts(
+  connection: anchor.web3.Connection,
+  mint: PublicKey,
+  source: PublicKey,
+  destination: PublicKey,
+  owner: PublicKey,
+  amount: BN
+): Promise<anchor.web3.AccountMeta[]> {
+  const ix = createTransferCheckedInstruction(
+    source,
+    mint,
+    destination,
+    owner,
+    BigInt(amount.toString()),
+    0,
+    [],
+    TOKEN_2022_PROGRAM_ID
+  );
+  await addExtraAccountMetasForExecute(
+    connection,
+    ix,
+    HOOK_PROGRAM_ID,
+    source,
+    mint,
+    destination,
+    owner,
+    BigInt(amount.toString()),
+    "confirmed"
+  );
+  // Drop source, mint, destination and owner
+  return ix.keys.slice(4);
+}
+
 describe("spl_program", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
@@ -38,6 +284,7 @@ describe("spl_program", () => {
   let mintBump;
   let ata: PublicKey;
   let extraMetasAccount: PublicKey;
+  let approveAccount: PublicKey;
 
   // Token configuration
   const decimals = 9;
@@ -49,14 +296,24 @@ describe("spl_program", () => {
   before(async () => {
     // Derive mint PDA
     [mintPda, mintBump] = PublicKey.findProgramAddressSync(
-      [Buffer.from("mint"), provider.wallet.publicKey.toBuffer()],
+      [
+        Buffer.from("mint"),
+        provider.wallet.publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
       program.programId
     );
 
     // Derive extra metas account PDA
     [extraMetasAccount] = PublicKey.findProgramAddressSync(
       [Buffer.from("extra-account-metas"), mintPda.toBuffer()],
-      program.programId
+      HOOK_PROGRAM_ID
+    );
+
+    // Derive transfer-hook approve account PDA
+    [approveAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("approve-account"), mintPda.toBuffer()],
+      HOOK_PROGRAM_ID
     );
 
     // Derive associated token account
@@ -69,15 +326,39 @@ describe("spl_program", () => {
   it("createMintAccount: initializes mint + ATA with zero balance", async () => {
     // Create mint account with metadata
     await program.methods
-      .createMintAccount(decimals, TOKEN_NAME, TOKEN_SYMBOL, METADATA_URI)
+      .createMintAccount(decimals, TOKEN_NAME, TOKEN_SYMBOL, METADATA_URI, {
+        transferHook: false,
+        transferFee: null,
+        interestRate: null,
+        nonTransferable: false,
+        permanentDelegate: null,
+        defaultFrozen: false,
+        confidentialTransfer: null,
+        groupPointer: false,
+        groupMemberPointer: false,
+      }, new BN(0), null)
       .accountsStrict({
         payer: payer.publicKey,
         authority: payer.publicKey,
         receiver: payer.publicKey,
         mint: mintPda,
+        mintRegistry: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_registry"), payer.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mintPda.toBuffer()],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mintPda.toBuffer()],
+          program.programId
+        )[0],
         mintTokenAccount: ata,
+        approveAccount: approveAccount,
         extraMetasAccount: extraMetasAccount,
         systemProgram: anchor.web3.SystemProgram.programId,
+        hookProgram: HOOK_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
       })
@@ -97,13 +378,30 @@ describe("spl_program", () => {
   it("mint_tokens: mints fresh supply into the ATA", async () => {
     const mintAmount = new BN(1_000 * 10 ** decimals);
 
+    await addMinter(program, payer, mintPda);
     // Mint tokens to ATA
     await program.methods
       .mintTokens(mintAmount)
       .accountsStrict({
         mint: mintPda,
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mintPda.toBuffer()],
+          program.programId
+        )[0],
         to: ata,
         authority: payer.publicKey,
+        minter: PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("minter"),
+            mintPda.toBuffer(),
+            payer.publicKey.toBuffer(),
+          ],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mintPda.toBuffer()],
+          program.programId
+        )[0],
         tokenProgram: TOKEN_2022_PROGRAM_ID,
       })
       .signers([payer])
@@ -145,6 +443,52 @@ describe("spl_program", () => {
     expect(acct.amount).to.eql(BigInt((1_000 - 200) * 10 ** decimals));
   });
 
+  it("token metadata: updates fields, adds and removes custom keys", async () => {
+    const accounts = {
+      updateAuthority: payer.publicKey,
+      mint: mintPda,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+
+    await program.methods
+      .updateTokenMetadataField({ name: {} }, "MetaWin v2")
+      .accountsStrict(accounts)
+      .signers([payer])
+      .rpc();
+    await program.methods
+      .updateTokenMetadataField({ key: ["website"] }, "https://example.com")
+      .accountsStrict(accounts)
+      .signers([payer])
+      .rpc();
+
+    let metadata = await getTokenMetadata(
+      provider.connection,
+      mintPda,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(metadata.name).to.eql("MetaWin v2");
+    expect(metadata.additionalMetadata).to.deep.include([
+      "website",
+      "https://example.com",
+    ]);
+
+    await program.methods
+      .removeTokenMetadataKey("website", false)
+      .accountsStrict(accounts)
+      .signers([payer])
+      .rpc();
+
+    metadata = await getTokenMetadata(
+      provider.connection,
+      mintPda,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(metadata.additionalMetadata).to.have.length(0);
+  });
+
   it("freeze and thaw the token account", async () => {
     // Freeze token account
     await program.methods
@@ -165,6 +509,10 @@ describe("spl_program", () => {
         account: ata,
         mint: mintPda,
         freezeAuthority: payer.publicKey,
+        kycConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("kyc_config"), mintPda.toBuffer()],
+          program.programId
+        )[0],
         tokenProgram: TOKEN_2022_PROGRAM_ID,
       })
       .signers([payer])
@@ -211,3 +559,3730 @@ describe("spl_program", () => {
   //   }
   // });
 });
+
+describe("transfer_hook", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+  const hookProgram = anchor.workspace.SplHook as Program<SplHook>;
+
+  // Fresh authority so the hooked mint gets its own mint PDA
+  const hookAuthority = Keypair.generate();
+  const recipient = Keypair.generate();
+  const decimals = 6;
+
+  let mint: PublicKey;
+  let approveAccount: PublicKey;
+  let extraMetasAccount: PublicKey;
+  let sourceAta: PublicKey;
+  let destinationAta: PublicKey;
+
+  before(async () => {
+    const sig = await provider.connection.requestAirdrop(
+      hookAuthority.publicKey,
+      2 * LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(sig, "confirmed");
+
+    [mint] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("mint"),
+        hookAuthority.publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    [approveAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("approve-account"), mint.toBuffer()],
+      HOOK_PROGRAM_ID
+    );
+    [extraMetasAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("extra-account-metas"), mint.toBuffer()],
+      HOOK_PROGRAM_ID
+    );
+    sourceAta = associatedAddress({ mint, owner: hookAuthority.publicKey });
+  });
+
+  it("createMintAccount: creates a hooked mint and its approve account", async () => {
+    await program.methods
+      .createMintAccount(decimals, "Hooked", "HOOK", "", {
+        transferHook: true,
+        transferFee: null,
+        interestRate: null,
+        nonTransferable: false,
+        permanentDelegate: null,
+        defaultFrozen: false,
+        confidentialTransfer: null,
+        groupPointer: false,
+        groupMemberPointer: false,
+      }, new BN(0), null)
+      .accountsStrict({
+        payer: hookAuthority.publicKey,
+        authority: hookAuthority.publicKey,
+        receiver: hookAuthority.publicKey,
+        mint,
+        mintRegistry: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_registry"), hookAuthority.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mint.toBuffer()],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mint.toBuffer()],
+          program.programId
+        )[0],
+        mintTokenAccount: sourceAta,
+        approveAccount,
+        extraMetasAccount,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        hookProgram: HOOK_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([hookAuthority])
+      .rpc();
+
+    await addMinter(program, hookAuthority, mint);
+    await program.methods
+      .mintTokens(new BN(1_000 * 10 ** decimals))
+      .accountsStrict({
+        mint,
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mint.toBuffer()],
+          program.programId
+        )[0],
+        to: sourceAta,
+        authority: hookAuthority.publicKey,
+        minter: PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("minter"),
+            mint.toBuffer(),
+            hookAuthority.publicKey.toBuffer(),
+          ],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mint.toBuffer()],
+          program.programId
+        )[0],
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([hookAuthority])
+      .rpc();
+
+    destinationAta = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      hookAuthority,
+      mint,
+      recipient.publicKey,
+      {},
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const approve = await hookProgram.account.approveAccount.fetch(approveAccount);
+    expect(approve.mint.toBase58()).to.eql(mint.toBase58());
+    expect(approve.maxTransferAmount.toNumber()).to.eql(0);
+  });
+
+  // Build a transfer_checked that resolves the hook's extra accounts
+  const hookedTransfer = async (amount: bigint) => {
+    const ix = await createTransferCheckedWithTransferHookInstruction(
+      provider.connection,
+      sourceAta,
+      mint,
+      destinationAta,
+      hookAuthority.publicKey,
+      amount,
+      decimals,
+      [],
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    return sendAndConfirmTransaction(
+      provider.connection,
+      new Transaction().add(ix),
+      [hookAuthority]
+    );
+  };
+
+  it("execute: validates transfers of the hooked mint", async () => {
+    await hookedTransfer(BigInt(10 * 10 ** decimals));
+
+    const destination = await getAccount(
+      provider.connection,
+      destinationAta,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(destination.amount).to.eql(BigInt(10 * 10 ** decimals));
+  });
+
+  it("update_transfer_policy: rejects transfers above the limit", async () => {
+    await hookProgram.methods
+      .updateTransferPolicy(new BN(5 * 10 ** decimals))
+      .accountsStrict({
+        authority: hookAuthority.publicKey,
+        mint,
+        approveAccount,
+      })
+      .signers([hookAuthority])
+      .rpc();
+
+    try {
+      await hookedTransfer(BigInt(10 * 10 ** decimals));
+      assert.fail("Expected the hook to reject the transfer");
+    } catch (err: any) {
+      expect(String(err)).to.not.match(/Expected the hook/);
+    }
+
+    await hookedTransfer(BigInt(5 * 10 ** decimals));
+    const destination = await getAccount(
+      provider.connection,
+      destinationAta,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(destination.amount).to.eql(BigInt(15 * 10 ** decimals));
+  });
+});
+
+describe("transfer_fee", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  // Fresh authority so the fee mint gets its own mint PDA
+  const feeAuthority = Keypair.generate();
+  const recipient = Keypair.generate();
+  const decimals = 6;
+  const FEE_BPS = 100; // 1%
+
+  let mint: PublicKey;
+  let sourceAta: PublicKey;
+  let destinationAta: PublicKey;
+
+  before(async () => {
+    const sig = await provider.connection.requestAirdrop(
+      feeAuthority.publicKey,
+      2 * LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(sig, "confirmed");
+
+    [mint] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("mint"),
+        feeAuthority.publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    sourceAta = associatedAddress({ mint, owner: feeAuthority.publicKey });
+  });
+
+  it("createMintAccount: creates a mint with a transfer fee", async () => {
+    await program.methods
+      .createMintAccount(decimals, "Fee Token", "FEE", "", {
+        transferHook: false,
+        transferFee: {
+          transferFeeBasisPoints: FEE_BPS,
+          maximumFee: new BN(1_000 * 10 ** decimals),
+        },
+        interestRate: null,
+        nonTransferable: false,
+        permanentDelegate: null,
+        defaultFrozen: false,
+        confidentialTransfer: null,
+        groupPointer: false,
+        groupMemberPointer: false,
+      }, new BN(0), null)
+      .accountsStrict({
+        payer: feeAuthority.publicKey,
+        authority: feeAuthority.publicKey,
+        receiver: feeAuthority.publicKey,
+        mint,
+        mintRegistry: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_registry"), feeAuthority.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mint.toBuffer()],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mint.toBuffer()],
+          program.programId
+        )[0],
+        mintTokenAccount: sourceAta,
+        approveAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from("approve-account"), mint.toBuffer()],
+          HOOK_PROGRAM_ID
+        )[0],
+        extraMetasAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from("extra-account-metas"), mint.toBuffer()],
+          HOOK_PROGRAM_ID
+        )[0],
+        systemProgram: anchor.web3.SystemProgram.programId,
+        hookProgram: HOOK_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([feeAuthority])
+      .rpc();
+
+    await addMinter(program, feeAuthority, mint);
+    await program.methods
+      .mintTokens(new BN(1_000 * 10 ** decimals))
+      .accountsStrict({
+        mint,
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mint.toBuffer()],
+          program.programId
+        )[0],
+        to: sourceAta,
+        authority: feeAuthority.publicKey,
+        minter: PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("minter"),
+            mint.toBuffer(),
+            feeAuthority.publicKey.toBuffer(),
+          ],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mint.toBuffer()],
+          program.programId
+        )[0],
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([feeAuthority])
+      .rpc();
+
+    destinationAta = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      feeAuthority,
+      mint,
+      recipient.publicKey,
+      {},
+      TOKEN_2022_PROGRAM_ID
+    );
+  });
+
+  it("harvest and withdraw withheld fees to the treasury", async () => {
+    const amount = BigInt(100 * 10 ** decimals);
+    const fee = (amount * BigInt(FEE_BPS)) / BigInt(10_000);
+    await transferCheckedWithFee(
+      provider.connection,
+      feeAuthority,
+      sourceAta,
+      mint,
+      destinationAta,
+      feeAuthority,
+      amount,
+      decimals,
+      fee,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // Harvest the fee withheld in the recipient's account into the mint
+    await program.methods
+      .harvestTransferFees()
+      .accountsStrict({
+        mint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: destinationAta, isSigner: false, isWritable: true },
+      ])
+      .rpc();
+
+    const before = await getAccount(
+      provider.connection,
+      sourceAta,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // Withdraw everything withheld in the mint back to the authority's ATA
+    await program.methods
+      .withdrawTransferFees()
+      .accountsStrict({
+        withdrawAuthority: feeAuthority.publicKey,
+        mint,
+        treasury: sourceAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([feeAuthority])
+      .rpc();
+
+    const after = await getAccount(
+      provider.connection,
+      sourceAta,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(after.amount - before.amount).to.eql(fee);
+  });
+
+  it("update_transfer_fee_schedule: rejects basis points above 10000", async () => {
+    try {
+      await program.methods
+        .updateTransferFeeSchedule(10_001, new BN(0))
+        .accountsStrict({
+          feeAuthority: feeAuthority.publicKey,
+          mint,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([feeAuthority])
+        .rpc();
+      assert.fail("Expected InvalidFeeBasisPoints");
+    } catch (err: any) {
+      expect(String(err)).to.match(/InvalidFeeBasisPoints/);
+    }
+  });
+});
+
+describe("interest_bearing", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const rateAuthority = Keypair.generate();
+  const decimals = 6;
+  let mint: PublicKey;
+
+  before(async () => {
+    await airdrop(provider, rateAuthority.publicKey);
+    ({ mint } = await createMintWithExtensions(
+      program,
+      rateAuthority,
+      decimals,
+      { interestRate: 500 }
+    ));
+  });
+
+  it("get_ui_amount: returns the UI amount as return data", async () => {
+    const ui = await program.methods
+      .getUiAmount(new BN(10 ** decimals))
+      .accountsStrict({ mint, tokenProgram: TOKEN_2022_PROGRAM_ID })
+      .view();
+    expect(parseFloat(ui)).to.be.gte(1);
+  });
+
+  it("update_interest_rate: only the rate authority can change the rate", async () => {
+    await program.methods
+      .updateInterestRate(250)
+      .accountsStrict({
+        rateAuthority: rateAuthority.publicKey,
+        mint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([rateAuthority])
+      .rpc();
+
+    const stranger = Keypair.generate();
+    await airdrop(provider, stranger.publicKey, 1);
+    try {
+      await program.methods
+        .updateInterestRate(10_000)
+        .accountsStrict({
+          rateAuthority: stranger.publicKey,
+          mint,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Expected the token program to reject the rate update");
+    } catch (err: any) {
+      expect(String(err)).to.not.match(/Expected the token program/);
+    }
+  });
+});
+
+describe("non_transferable", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const issuer = Keypair.generate();
+  const decimals = 0;
+  let mint: PublicKey;
+  let ata: PublicKey;
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey);
+    ({ mint, ata } = await createMintWithExtensions(program, issuer, decimals, {
+      nonTransferable: true,
+    }));
+  });
+
+  it("mint_tokens and burn_tokens keep working for soulbound mints", async () => {
+    await program.methods
+      .mintTokens(new BN(2))
+      .accountsStrict({
+        mint,
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mint.toBuffer()],
+          program.programId
+        )[0],
+        to: ata,
+        authority: issuer.publicKey,
+        minter: PublicKey.findProgramAddressSync(
+          [Buffer.from("minter"), mint.toBuffer(), issuer.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mint.toBuffer()],
+          program.programId
+        )[0],
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+
+    await program.methods
+      .burnTokens(new BN(1))
+      .accountsStrict({
+        mint,
+        from: ata,
+        authority: issuer.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+
+    const acct = await getAccount(
+      provider.connection,
+      ata,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(acct.amount).to.eql(BigInt(1));
+  });
+
+  it("initialize_pool_stake: rejects soulbound mints", async () => {
+    const [pool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("staking_pool"), mint.toBuffer()],
+      program.programId
+    );
+    const vault = associatedAddress({ mint, owner: pool });
+    try {
+      await program.methods
+        .initializePoolStake(new BN(86_400))
+        .accountsStrict({
+          admin: issuer.publicKey,
+          stakeMint: mint,
+          rewardMint: mint,
+          pool,
+          stakeVault: vault,
+          rewardVault: vault,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([issuer])
+        .rpc();
+      assert.fail("Expected NonTransferableMint");
+    } catch (err: any) {
+      expect(String(err)).to.match(/NonTransferableMint/);
+    }
+  });
+});
+
+describe("permanent_delegate", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const issuer = Keypair.generate();
+  const holder = Keypair.generate();
+  const decimals = 6;
+  let mint: PublicKey;
+  let issuerAta: PublicKey;
+  let holderAta: PublicKey;
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey);
+    ({ mint, ata: issuerAta } = await createMintWithExtensions(
+      program,
+      issuer,
+      decimals,
+      { permanentDelegate: issuer.publicKey }
+    ));
+    holderAta = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      issuer,
+      mint,
+      holder.publicKey,
+      {},
+      TOKEN_2022_PROGRAM_ID
+    );
+    await program.methods
+      .mintTokens(new BN(100 * 10 ** decimals))
+      .accountsStrict({
+        mint,
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mint.toBuffer()],
+          program.programId
+        )[0],
+        to: holderAta,
+        authority: issuer.publicKey,
+        minter: PublicKey.findProgramAddressSync(
+          [Buffer.from("minter"), mint.toBuffer(), issuer.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mint.toBuffer()],
+          program.programId
+        )[0],
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+  });
+
+  it("clawback: recovers tokens into the delegate's account", async () => {
+    await program.methods
+      .clawback(new BN(40 * 10 ** decimals), 1)
+      .accountsStrict({
+        delegate: issuer.publicKey,
+        mint,
+        source: holderAta,
+        destination: issuerAta,
+        freezeAuthority: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+
+    const holderAcct = await getAccount(
+      provider.connection,
+      holderAta,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(holderAcct.amount).to.eql(BigInt(60 * 10 ** decimals));
+  });
+
+  it("force_transfer: rejects signers other than the permanent delegate", async () => {
+    try {
+      await program.methods
+        .forceTransfer(new BN(1), 2)
+        .accountsStrict({
+          delegate: holder.publicKey,
+          mint,
+          source: issuerAta,
+          destination: holderAta,
+          freezeAuthority: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([holder])
+        .rpc();
+      assert.fail("Expected the permanent delegate constraint to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/PermanentDelegate/);
+    }
+  });
+
+  it("clawback: thaws and re-freezes a frozen holder with the freeze authority", async () => {
+    const freeze = await program.methods
+      .freezeTokenAccount()
+      .accountsStrict({
+        account: holderAta,
+        mint,
+        freezeAuthority: issuer.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+    expect(freeze).to.be.a("string");
+
+    const clawback = (freezeAuthority: PublicKey | null) =>
+      program.methods
+        .clawback(new BN(10 * 10 ** decimals), 3)
+        .accountsStrict({
+          delegate: issuer.publicKey,
+          mint,
+          source: holderAta,
+          destination: issuerAta,
+          freezeAuthority,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([issuer])
+        .rpc();
+
+    try {
+      await clawback(null);
+      assert.fail("Expected a frozen source without the freeze authority to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/SourceFrozen/);
+    }
+
+    await clawback(issuer.publicKey);
+
+    const holderAcct = await getAccount(
+      provider.connection,
+      holderAta,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(holderAcct.amount).to.eql(BigInt(50 * 10 ** decimals));
+    expect(holderAcct.isFrozen).to.eql(true);
+  });
+});
+
+describe("kyc", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const issuer = Keypair.generate();
+  const approver = Keypair.generate();
+  const stranger = Keypair.generate();
+  let mint: PublicKey;
+  let ata: PublicKey;
+  let kycConfig: PublicKey;
+  let kycRecord: PublicKey;
+  let kycFreezeAuthority: PublicKey;
+
+  const thawKyc = (signer: Keypair) =>
+    program.methods
+      .thawKycTokenAccount()
+      .accountsStrict({
+        account: ata,
+        mint,
+        kycConfig,
+        kycFreezeAuthority,
+        kycRecord,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([signer])
+      .rpc();
+
+  const isFrozen = async () =>
+    (
+      await getAccount(
+        provider.connection,
+        ata,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).isFrozen;
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey);
+    await airdrop(provider, approver.publicKey, 1);
+    await airdrop(provider, stranger.publicKey, 1);
+    ({ mint, ata } = await createMintWithExtensions(program, issuer, 6, {
+      defaultFrozen: true,
+    }));
+    [kycConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("kyc_config"), mint.toBuffer()],
+      program.programId
+    );
+    [kycRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from("kyc_record"), mint.toBuffer(), issuer.publicKey.toBuffer()],
+      program.programId
+    );
+    [kycFreezeAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("kyc_freeze_authority"), mint.toBuffer()],
+      program.programId
+    );
+  });
+
+  it("new token accounts start frozen", async () => {
+    expect(await isFrozen()).to.eql(true);
+  });
+
+  it("set_kyc_approver_admin: hands the freeze authority to the program", async () => {
+    await program.methods
+      .setKycApproverAdmin(approver.publicKey)
+      .accountsStrict({
+        authority: issuer.publicKey,
+        mint,
+        kycConfig,
+        kycFreezeAuthority,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+
+    const info = await getMint(
+      provider.connection,
+      mint,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(info.freezeAuthority?.toBase58()).to.eql(
+      kycFreezeAuthority.toBase58()
+    );
+  });
+
+  it("thaw_token_account: rejects KYC-gated mints", async () => {
+    try {
+      await program.methods
+        .thawTokenAccount()
+        .accountsStrict({
+          account: ata,
+          mint,
+          freezeAuthority: issuer.publicKey,
+          kycConfig,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([issuer])
+        .rpc();
+      assert.fail("Expected the plain thaw to be rejected");
+    } catch (err: any) {
+      expect(String(err)).to.match(/KycGatedMint/);
+    }
+  });
+
+  it("thaw_kyc_token_account: requires a KYC record", async () => {
+    try {
+      await thawKyc(stranger);
+      assert.fail("Expected the missing KYC record to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/AccountNotInitialized/);
+    }
+  });
+
+  it("thaw_kyc_token_account: anyone can thaw once the approver approves the owner", async () => {
+    await program.methods
+      .approveKycWallet(new BN(0))
+      .accountsStrict({
+        approver: approver.publicKey,
+        mint,
+        kycConfig,
+        wallet: issuer.publicKey,
+        kycRecord,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([approver])
+      .rpc();
+
+    await thawKyc(stranger);
+    expect(await isFrozen()).to.eql(false);
+  });
+
+  it("freeze_kyc_token_account: the approver can freeze a holder again", async () => {
+    const freeze = (signer: Keypair) =>
+      program.methods
+        .freezeKycTokenAccount()
+        .accountsStrict({
+          approver: signer.publicKey,
+          account: ata,
+          mint,
+          kycConfig,
+          kycFreezeAuthority,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([signer])
+        .rpc();
+
+    try {
+      await freeze(stranger);
+      assert.fail("Expected a non-approver freeze to be rejected");
+    } catch (err: any) {
+      expect(String(err)).to.match(/ConstraintHasOne/);
+    }
+
+    await freeze(approver);
+    expect(await isFrozen()).to.eql(true);
+  });
+});
+
+describe("authority_management", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const issuer = Keypair.generate();
+  const newFreezeAuthority = Keypair.generate();
+  let mint: PublicKey;
+  let ata: PublicKey;
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey);
+    ({ mint, ata } = await createMintWithExtensions(program, issuer, 6, {}));
+  });
+
+  it("set_mint_authority: rotates the freeze authority", async () => {
+    await program.methods
+      .setMintAuthority({ freezeAccount: {} }, newFreezeAuthority.publicKey)
+      .accountsStrict({
+        currentAuthority: issuer.publicKey,
+        mint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+
+    const info = await getMint(
+      provider.connection,
+      mint,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(info.freezeAuthority?.toBase58()).to.eql(
+      newFreezeAuthority.publicKey.toBase58()
+    );
+  });
+
+  it("set_mint_authority: rejects the program-held mint authority", async () => {
+    try {
+      await program.methods
+        .setMintAuthority({ mintTokens: {} }, null)
+        .accountsStrict({
+          currentAuthority: issuer.publicKey,
+          mint,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([issuer])
+        .rpc();
+      assert.fail("Expected rotating the mint authority to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/ProgramMintAuthority/);
+    }
+  });
+
+  it("renounce_mint_authority: renouncing the mint authority fixes the supply", async () => {
+    await program.methods
+      .renounceMintAuthority()
+      .accountsStrict({
+        authority: issuer.publicKey,
+        mint,
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mint.toBuffer()],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mint.toBuffer()],
+          program.programId
+        )[0],
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+
+    try {
+      await program.methods
+        .mintTokens(new BN(1))
+        .accountsStrict({
+          mint,
+          mintConfig: PublicKey.findProgramAddressSync(
+            [Buffer.from("mint_config"), mint.toBuffer()],
+            program.programId
+          )[0],
+          to: ata,
+          authority: issuer.publicKey,
+          minter: PublicKey.findProgramAddressSync(
+            [
+              Buffer.from("minter"),
+              mint.toBuffer(),
+              issuer.publicKey.toBuffer(),
+            ],
+            program.programId
+          )[0],
+          mintAuthority: PublicKey.findProgramAddressSync(
+            [Buffer.from("mint_authority"), mint.toBuffer()],
+            program.programId
+          )[0],
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([issuer])
+        .rpc();
+      assert.fail("Expected minting to fail after renouncing");
+    } catch (err: any) {
+      expect(String(err)).to.not.match(/Expected minting/);
+    }
+  });
+});
+
+describe("mint_registry", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const treasury = Keypair.generate();
+  const [registry] = PublicKey.findProgramAddressSync(
+    [Buffer.from("mint_registry"), treasury.publicKey.toBuffer()],
+    program.programId
+  );
+  const mints: PublicKey[] = [];
+
+  before(async () => {
+    await airdrop(provider, treasury.publicKey, 5);
+  });
+
+  it("createMintAccount: one authority launches several mints", async () => {
+    for (let nonce = 0; nonce < 3; nonce++) {
+      const { mint } = await createMintWithExtensions(
+        program,
+        treasury,
+        6,
+        {},
+        nonce
+      );
+      mints.push(mint);
+    }
+
+    const account = await program.account.mintRegistry.fetch(registry);
+    expect(account.authority.toBase58()).to.eql(treasury.publicKey.toBase58());
+    expect(account.mints.map((m) => m.toBase58())).to.eql(
+      mints.map((m) => m.toBase58())
+    );
+  });
+
+  it("createMintAccount: rejects a reused nonce", async () => {
+    try {
+      await createMintWithExtensions(program, treasury, 6, {}, 1);
+      assert.fail("Expected a reused nonce to fail");
+    } catch (err: any) {
+      expect(String(err)).to.not.match(/Expected a reused nonce/);
+    }
+
+    const account = await program.account.mintRegistry.fetch(registry);
+    expect(account.mints.length).to.eql(mints.length);
+  });
+});
+
+describe("supply_cap", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const issuer = Keypair.generate();
+  const decimals = 6;
+  const maxSupply = new BN(1_000 * 10 ** decimals);
+  let mint: PublicKey;
+  let ata: PublicKey;
+  let mintConfig: PublicKey;
+
+  const mintTo = (amount: BN) =>
+    program.methods
+      .mintTokens(amount)
+      .accountsStrict({
+        mint,
+        mintConfig,
+        to: ata,
+        authority: issuer.publicKey,
+        minter: PublicKey.findProgramAddressSync(
+          [Buffer.from("minter"), mint.toBuffer(), issuer.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mint.toBuffer()],
+          program.programId
+        )[0],
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey);
+    ({ mint, ata } = await createMintWithExtensions(
+      program,
+      issuer,
+      decimals,
+      {},
+      0,
+      maxSupply
+    ));
+    [mintConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_config"), mint.toBuffer()],
+      program.programId
+    );
+  });
+
+  it("mint_tokens: mints up to the cap and rejects anything beyond", async () => {
+    await mintTo(maxSupply.sub(new BN(10)));
+
+    const remaining = await program.methods
+      .getRemainingSupply()
+      .accountsStrict({ mint, mintConfig })
+      .view();
+    expect(remaining.toString()).to.eql("10");
+
+    try {
+      await mintTo(new BN(11));
+      assert.fail("Expected minting past the cap to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/MaxSupplyExceeded/);
+    }
+  });
+
+  it("lower_max_supply: lowers the cap but never raises it", async () => {
+    const lowered = maxSupply.sub(new BN(5));
+    await program.methods
+      .lowerMaxSupply(lowered)
+      .accountsStrict({
+        authority: issuer.publicKey,
+        mint,
+        mintConfig,
+      })
+      .signers([issuer])
+      .rpc();
+
+    const config = await program.account.mintConfig.fetch(mintConfig);
+    expect(config.maxSupply.toString()).to.eql(lowered.toString());
+
+    try {
+      await program.methods
+        .lowerMaxSupply(maxSupply)
+        .accountsStrict({
+          authority: issuer.publicKey,
+          mint,
+          mintConfig,
+        })
+        .signers([issuer])
+        .rpc();
+      assert.fail("Expected raising the cap to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/MaxSupplyRaised/);
+    }
+  });
+});
+
+describe("minter_registry", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const admin = Keypair.generate();
+  const bridge = Keypair.generate();
+  let mint: PublicKey;
+  let ata: PublicKey;
+  let mintConfig: PublicKey;
+  let bridgeMinter: PublicKey;
+
+  const mintAsBridge = (amount: BN) =>
+    program.methods
+      .mintTokens(amount)
+      .accountsStrict({
+        mint,
+        mintConfig,
+        to: ata,
+        authority: bridge.publicKey,
+        minter: bridgeMinter,
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mint.toBuffer()],
+          program.programId
+        )[0],
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([bridge])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider, admin.publicKey);
+    ({ mint, ata } = await createMintWithExtensions(program, admin, 6, {}));
+    [mintConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_config"), mint.toBuffer()],
+      program.programId
+    );
+    bridgeMinter = await addMinter(
+      program,
+      admin,
+      mint,
+      bridge.publicKey,
+      new BN(100),
+      new BN(60)
+    );
+  });
+
+  it("mint_tokens: consumes the minter's allowance", async () => {
+    await mintAsBridge(new BN(50));
+
+    const entry = await program.account.minter.fetch(bridgeMinter);
+    expect(entry.allowance.toString()).to.eql("50");
+    expect(entry.mintedThisEpoch.toString()).to.eql("50");
+  });
+
+  it("mint_tokens: enforces the per-epoch rate limit", async () => {
+    try {
+      await mintAsBridge(new BN(20));
+      assert.fail("Expected the epoch limit to be enforced");
+    } catch (err: any) {
+      expect(String(err)).to.match(/EpochLimitExceeded/);
+    }
+  });
+
+  it("add_minter: a disabled minter cannot mint", async () => {
+    await addMinter(
+      program,
+      admin,
+      mint,
+      bridge.publicKey,
+      new BN(50),
+      new BN(60),
+      false
+    );
+
+    try {
+      await mintAsBridge(new BN(1));
+      assert.fail("Expected a disabled minter to be rejected");
+    } catch (err: any) {
+      expect(String(err)).to.match(/MinterDisabled/);
+    }
+  });
+
+  it("remove_minter: closes the minter entry", async () => {
+    await program.methods
+      .removeMinter()
+      .accountsStrict({
+        authority: admin.publicKey,
+        mint,
+        mintConfig,
+        minter: bridgeMinter,
+      })
+      .signers([admin])
+      .rpc();
+
+    const info = await provider.connection.getAccountInfo(bridgeMinter);
+    expect(info).to.eql(null);
+  });
+});
+
+describe("multisig", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const admin = Keypair.generate();
+  const signerB = Keypair.generate();
+  const signerC = Keypair.generate();
+  const createKey = Keypair.generate();
+  let mint: PublicKey;
+  let ata: PublicKey;
+  let multisig: PublicKey;
+
+  const proposalAddress = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        multisig.toBuffer(),
+        new BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+  const expiresAt = () => new BN(Math.floor(Date.now() / 1000) + 3600);
+
+  before(async () => {
+    await airdrop(provider, admin.publicKey);
+    await airdrop(provider, signerB.publicKey);
+    ({ mint, ata } = await createMintWithExtensions(program, admin, 6, {}));
+    [multisig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("multisig"), createKey.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .createMultisig(
+        [admin.publicKey, signerB.publicKey, signerC.publicKey],
+        2
+      )
+      .accountsStrict({
+        payer: admin.publicKey,
+        createKey: createKey.publicKey,
+        multisig,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin, createKey])
+      .rpc();
+
+    // The multisig mints through its own minter entry
+    await addMinter(program, admin, mint, multisig, new BN(1_000));
+  });
+
+  it("execute_proposal: mints only after reaching the threshold", async () => {
+    const proposal = proposalAddress(0);
+    await program.methods
+      .createProposal(
+        { mintTokens: { mint, to: ata, amount: new BN(100) } } as any,
+        expiresAt()
+      )
+      .accountsStrict({
+        proposer: admin.publicKey,
+        multisig,
+        proposal,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+
+    const execute = () =>
+      program.methods
+        .executeProposal()
+        .accountsStrict({
+          executor: admin.publicKey,
+          multisig,
+          proposal,
+          mint,
+          tokenAccount: ata,
+          minter: PublicKey.findProgramAddressSync(
+            [Buffer.from("minter"), mint.toBuffer(), multisig.toBuffer()],
+            program.programId
+          )[0],
+          mintConfig: PublicKey.findProgramAddressSync(
+            [Buffer.from("mint_config"), mint.toBuffer()],
+            program.programId
+          )[0],
+          mintAuthority: PublicKey.findProgramAddressSync(
+            [Buffer.from("mint_authority"), mint.toBuffer()],
+            program.programId
+          )[0],
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([admin])
+        .rpc();
+
+    try {
+      await execute();
+      assert.fail("Expected execution below the threshold to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/NotEnoughApprovals/);
+    }
+
+    await program.methods
+      .approveProposal()
+      .accountsStrict({ signer: signerB.publicKey, multisig, proposal })
+      .signers([signerB])
+      .rpc();
+    await execute();
+
+    const acct = await getAccount(
+      provider.connection,
+      ata,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(acct.amount).to.eql(BigInt(100));
+
+    try {
+      await execute();
+      assert.fail("Expected a second execution to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/AlreadyExecuted/);
+    }
+  });
+
+  it("execute_proposal: freezes an account with the multisig as freeze authority", async () => {
+    await program.methods
+      .setMintAuthority({ freezeAccount: {} }, multisig)
+      .accountsStrict({
+        currentAuthority: admin.publicKey,
+        mint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([admin])
+      .rpc();
+
+    const proposal = proposalAddress(1);
+    await program.methods
+      .createProposal(
+        { freezeTokenAccount: { mint, account: ata } } as any,
+        expiresAt()
+      )
+      .accountsStrict({
+        proposer: signerB.publicKey,
+        multisig,
+        proposal,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signerB])
+      .rpc();
+    await program.methods
+      .approveProposal()
+      .accountsStrict({ signer: admin.publicKey, multisig, proposal })
+      .signers([admin])
+      .rpc();
+
+    await program.methods
+      .executeProposal()
+      .accountsStrict({
+        executor: admin.publicKey,
+        multisig,
+        proposal,
+        mint,
+        tokenAccount: ata,
+        minter: null,
+        mintConfig: null,
+        mintAuthority: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([admin])
+      .rpc();
+
+    const acct = await getAccount(
+      provider.connection,
+      ata,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(acct.isFrozen).to.eql(true);
+  });
+
+  it("transfer_config_authority: lets the multisig reconfigure its minter", async () => {
+    const mintConfig = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_config"), mint.toBuffer()],
+      program.programId
+    )[0];
+    const minter = PublicKey.findProgramAddressSync(
+      [Buffer.from("minter"), mint.toBuffer(), multisig.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods
+      .transferConfigAuthority()
+      .accountsStrict({ authority: admin.publicKey, mint, mintConfig, multisig })
+      .signers([admin])
+      .rpc();
+    expect(
+      (await program.account.mintConfig.fetch(mintConfig)).authority.toBase58()
+    ).to.eql(multisig.toBase58());
+
+    const proposal = proposalAddress(2);
+    await program.methods
+      .createProposal(
+        {
+          configureMinter: {
+            mint,
+            minter: multisig,
+            allowance: new BN(5_000),
+            epochLimit: new BN(0),
+            enabled: true,
+          },
+        } as any,
+        expiresAt()
+      )
+      .accountsStrict({
+        proposer: signerB.publicKey,
+        multisig,
+        proposal,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signerB])
+      .rpc();
+    await program.methods
+      .approveProposal()
+      .accountsStrict({ signer: admin.publicKey, multisig, proposal })
+      .signers([admin])
+      .rpc();
+    await program.methods
+      .executeProposal()
+      .accountsStrict({
+        executor: admin.publicKey,
+        multisig,
+        proposal,
+        mint,
+        tokenAccount: null,
+        minter,
+        mintConfig,
+        mintAuthority: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([admin])
+      .rpc();
+
+    const entry = await program.account.minter.fetch(minter);
+    expect(entry.allowance.toString()).to.eql("5000");
+  });
+
+  it("close_proposal: refunds settled proposals and keeps pending ones", async () => {
+    const pending = proposalAddress(3);
+    await program.methods
+      .createProposal(
+        { freezeTokenAccount: { mint, account: ata } } as any,
+        expiresAt()
+      )
+      .accountsStrict({
+        proposer: signerB.publicKey,
+        multisig,
+        proposal: pending,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([signerB])
+      .rpc();
+
+    const close = (proposal: PublicKey) =>
+      program.methods
+        .closeProposal()
+        .accountsStrict({ proposer: signerB.publicKey, multisig, proposal })
+        .rpc();
+
+    try {
+      await close(pending);
+      assert.fail("Expected closing a pending proposal to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/ProposalPending/);
+    }
+
+    // Proposal 2 was created by signerB and has been executed
+    const before = await provider.connection.getBalance(signerB.publicKey);
+    await close(proposalAddress(2));
+    expect(await provider.connection.getBalance(signerB.publicKey)).to.be.greaterThan(
+      before
+    );
+    expect(await provider.connection.getAccountInfo(proposalAddress(2))).to.eql(null);
+  });
+});
+
+describe("vesting", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const grantor = Keypair.generate();
+  const beneficiary = Keypair.generate();
+  const decimals = 6;
+  let mint: PublicKey;
+  let grantorAta: PublicKey;
+  let beneficiaryAta: PublicKey;
+
+  const vestingAddress = (id: number) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting"),
+        mint.toBuffer(),
+        beneficiary.publicKey.toBuffer(),
+        new BN(id).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+  const createVesting = async (
+    id: number,
+    amount: BN,
+    schedule: Record<string, unknown>
+  ) => {
+    const vesting = vestingAddress(id);
+    const vault = associatedAddress({ mint, owner: vesting });
+    await program.methods
+      .createVesting(new BN(id), amount, schedule as any)
+      .accountsStrict({
+        grantor: grantor.publicKey,
+        beneficiary: beneficiary.publicKey,
+        mint,
+        grantorTokenAccount: grantorAta,
+        vesting,
+        vault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([grantor])
+      .rpc();
+    return { vesting, vault };
+  };
+
+  before(async () => {
+    await airdrop(provider, grantor.publicKey);
+    await airdrop(provider, beneficiary.publicKey);
+    ({ mint, ata: grantorAta } = await createMintWithExtensions(
+      program,
+      grantor,
+      decimals,
+      {}
+    ));
+
+    await mintTokensTo(program, mint, grantor, grantorAta, new BN(1_000));
+
+    beneficiaryAta = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      beneficiary,
+      mint,
+      beneficiary.publicKey,
+      {},
+      TOKEN_2022_PROGRAM_ID
+    );
+  });
+
+  it("release_vested: pays out a fully vested schedule", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const { vesting, vault } = await createVesting(0, new BN(400), {
+      startTime: new BN(now - 200),
+      cliffTime: new BN(now - 100),
+      endTime: new BN(now - 10),
+      period: new BN(10),
+      revocable: false,
+    });
+
+    await program.methods
+      .releaseVested()
+      .accountsStrict({
+        beneficiary: beneficiary.publicKey,
+        mint,
+        vesting,
+        vault,
+        beneficiaryTokenAccount: beneficiaryAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([beneficiary])
+      .rpc();
+
+    const acct = await getAccount(
+      provider.connection,
+      beneficiaryAta,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(acct.amount).to.eql(BigInt(400));
+
+    const schedule = await program.account.vestingSchedule.fetch(vesting);
+    expect(schedule.releasedAmount.toString()).to.eql("400");
+  });
+
+  it("revoke_vesting: returns unvested tokens before the cliff", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const { vesting, vault } = await createVesting(1, new BN(600), {
+      startTime: new BN(now),
+      cliffTime: new BN(now + 1_000),
+      endTime: new BN(now + 2_000),
+      period: new BN(100),
+      revocable: true,
+    });
+
+    await program.methods
+      .revokeVesting()
+      .accountsStrict({
+        grantor: grantor.publicKey,
+        mint,
+        vesting,
+        vault,
+        grantorTokenAccount: grantorAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([grantor])
+      .rpc();
+
+    const acct = await getAccount(
+      provider.connection,
+      grantorAta,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(acct.amount).to.eql(BigInt(600));
+
+    const schedule = await program.account.vestingSchedule.fetch(vesting);
+    expect(schedule.revoked).to.eql(true);
+    expect(schedule.totalAmount.toString()).to.eql("0");
+  });
+
+  it("create_vesting / release_vested: works with a legacy SPL Token mint", async () => {
+    const legacyMint = await createMint(
+      provider.connection,
+      grantor,
+      grantor.publicKey,
+      null,
+      decimals,
+      undefined,
+      {},
+      TOKEN_PROGRAM_ID
+    );
+    const legacyGrantorAta = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      grantor,
+      legacyMint,
+      grantor.publicKey,
+      {},
+      TOKEN_PROGRAM_ID
+    );
+    const legacyBeneficiaryAta = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      beneficiary,
+      legacyMint,
+      beneficiary.publicKey,
+      {},
+      TOKEN_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      grantor,
+      legacyMint,
+      legacyGrantorAta,
+      grantor,
+      300,
+      [],
+      {},
+      TOKEN_PROGRAM_ID
+    );
+
+    const [vesting] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("vesting"),
+        legacyMint.toBuffer(),
+        beneficiary.publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const vault = PublicKey.findProgramAddressSync(
+      [vesting.toBuffer(), TOKEN_PROGRAM_ID.toBuffer(), legacyMint.toBuffer()],
+      ASSOCIATED_PROGRAM_ID
+    )[0];
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createVesting(new BN(0), new BN(300), {
+        startTime: new BN(now - 200),
+        cliffTime: new BN(now - 100),
+        endTime: new BN(now - 10),
+        period: new BN(10),
+        revocable: false,
+      } as any)
+      .accountsStrict({
+        grantor: grantor.publicKey,
+        beneficiary: beneficiary.publicKey,
+        mint: legacyMint,
+        grantorTokenAccount: legacyGrantorAta,
+        vesting,
+        vault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([grantor])
+      .rpc();
+
+    await program.methods
+      .releaseVested()
+      .accountsStrict({
+        beneficiary: beneficiary.publicKey,
+        mint: legacyMint,
+        vesting,
+        vault,
+        beneficiaryTokenAccount: legacyBeneficiaryAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([beneficiary])
+      .rpc();
+
+    const acct = await getAccount(
+      provider.connection,
+      legacyBeneficiaryAta,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    expect(acct.amount).to.eql(BigInt(300));
+  });
+});
+
+// Airdrop root and proofs built by `utils/merkle.rs` through
+// `cargo run --example airdrop_tree`, so the tests share the program's hashing
+function airdropTree(
+  allocations: { claimant: PublicKey; amount: BN }[]
+): { root: number[]; proofs: number[][][] } {
+  const output = execFileSync(
+    "cargo",
+    [
+      "run",
+      "--quiet",
+      "--example",
+      "airdrop_tree",
+      "--",
+      ...allocations.map(
+        ({ claimant, amount }) => `${claimant.toBase58()}:${amount.toString()}`
+      ),
+    ],
+    { cwd: join(__dirname, "..", "programs", "spl"), encoding: "utf8" }
+  );
+  return JSON.parse(output);
+}
+
+describe("merkle_airdrop", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const admin = Keypair.generate();
+  const recipients = [
+    Keypair.generate(),
+    Keypair.generate(),
+    Keypair.generate(),
+  ];
+  const amounts = [new BN(100), new BN(250), new BN(50)];
+  const total = amounts.reduce((sum, amount) => sum.add(amount), new BN(0));
+  const { root, proofs } = airdropTree(
+    recipients.map((r, i) => ({ claimant: r.publicKey, amount: amounts[i] }))
+  );
+  let mint: PublicKey;
+  let adminAta: PublicKey;
+
+  const createDistributor = async (id: number, endTime: number) => {
+    const [distributor] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("distributor"),
+        mint.toBuffer(),
+        admin.publicKey.toBuffer(),
+        new BN(id).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const vault = associatedAddress({ mint, owner: distributor });
+    await program.methods
+      .createDistributor(
+        new BN(id),
+        root,
+        recipients.length,
+        total,
+        new BN(Math.floor(Date.now() / 1000) - 10),
+        new BN(endTime)
+      )
+      .accountsStrict({
+        admin: admin.publicKey,
+        mint,
+        adminTokenAccount: adminAta,
+        distributor,
+        vault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([admin])
+      .rpc();
+    return { distributor, vault };
+  };
+
+  const claim = async (
+    accounts: { distributor: PublicKey; vault: PublicKey },
+    index: number,
+    amount: BN
+  ) => {
+    const claimant = recipients[index];
+    const claimantTokenAccount = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      admin,
+      mint,
+      claimant.publicKey,
+      {},
+      TOKEN_2022_PROGRAM_ID
+    );
+    await program.methods
+      .claimAirdrop(index, amount, proofs[index])
+      .accountsStrict({
+        claimant: claimant.publicKey,
+        mint,
+        distributor: accounts.distributor,
+        vault: accounts.vault,
+        claimantTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return claimantTokenAccount;
+  };
+
+  before(async () => {
+    await airdrop(provider, admin.publicKey, 5);
+    for (const r of recipients) await airdrop(provider, r.publicKey, 1);
+    ({ mint, ata: adminAta } = await createMintWithExtensions(
+      program,
+      admin,
+      6,
+      {}
+    ));
+    await mintTokensTo(program, mint, admin, adminAta, total.muln(2));
+  });
+
+  it("claim_airdrop: pays a valid proof once", async () => {
+    const accounts = await createDistributor(
+      0,
+      Math.floor(Date.now() / 1000) + 3_600
+    );
+
+    const ata = await claim(accounts, 1, amounts[1]);
+    const acct = await getAccount(
+      provider.connection,
+      ata,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(acct.amount).to.eql(BigInt(250));
+
+    try {
+      await claim(accounts, 1, amounts[1]);
+      assert.fail("Expected a second claim to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/AlreadyClaimed/);
+    }
+
+    try {
+      await claim(accounts, 0, amounts[0].addn(1));
+      assert.fail("Expected an inflated amount to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/InvalidProof/);
+    }
+
+    try {
+      await program.methods
+        .clawbackAirdrop()
+        .accountsStrict({
+          admin: admin.publicKey,
+          mint,
+          distributor: accounts.distributor,
+          vault: accounts.vault,
+          adminTokenAccount: adminAta,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([admin])
+        .rpc();
+      assert.fail("Expected clawback during the window to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/ClaimWindowOpen/);
+    }
+  });
+
+  it("clawback_airdrop: returns unclaimed tokens after expiry", async () => {
+    const accounts = await createDistributor(
+      1,
+      Math.floor(Date.now() / 1000) + 3
+    );
+    await new Promise((r) => setTimeout(r, 5_000));
+
+    await program.methods
+      .clawbackAirdrop()
+      .accountsStrict({
+        admin: admin.publicKey,
+        mint,
+        distributor: accounts.distributor,
+        vault: accounts.vault,
+        adminTokenAccount: adminAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([admin])
+      .rpc();
+
+    const vault = await getAccount(
+      provider.connection,
+      accounts.vault,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(vault.amount).to.eql(BigInt(0));
+    const info = await program.account.distributor.fetch(accounts.distributor);
+    expect(info.clawedBack).to.eql(true);
+  });
+
+  it("create_distributor: records what a transfer-fee vault actually received", async () => {
+    const { mint: feeMint, ata: feeAta } = await createMintWithExtensions(
+      program,
+      admin,
+      6,
+      {
+        transferFee: { transferFeeBasisPoints: 100, maximumFee: new BN(1_000) },
+      },
+      1
+    );
+    await mintTokensTo(program, feeMint, admin, feeAta, total);
+
+    const [distributor] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("distributor"),
+        feeMint.toBuffer(),
+        admin.publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const vault = associatedAddress({ mint: feeMint, owner: distributor });
+    await program.methods
+      .createDistributor(
+        new BN(0),
+        root,
+        recipients.length,
+        total,
+        new BN(Math.floor(Date.now() / 1000) - 10),
+        new BN(Math.floor(Date.now() / 1000) + 3_600)
+      )
+      .accountsStrict({
+        admin: admin.publicKey,
+        mint: feeMint,
+        adminTokenAccount: feeAta,
+        distributor,
+        vault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([admin])
+      .rpc();
+
+    // 1% of the 400 funded is withheld by the mint
+    const vaultAcct = await getAccount(
+      provider.connection,
+      vault,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(vaultAcct.amount).to.eql(BigInt(396));
+    const info = await program.account.distributor.fetch(distributor);
+    expect(info.totalAmount.toString()).to.eql("396");
+  });
+});
+
+describe("batch_operations", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const issuer = Keypair.generate();
+  const recipients = [
+    Keypair.generate(),
+    Keypair.generate(),
+    Keypair.generate(),
+  ];
+  let mint: PublicKey;
+  let issuerAta: PublicKey;
+  let recipientAtas: PublicKey[];
+
+  const asRemaining = (accounts: PublicKey[]) =>
+    accounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }));
+
+  const balanceOf = async (ata: PublicKey) =>
+    (
+      await getAccount(
+        provider.connection,
+        ata,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).amount;
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey);
+    ({ mint, ata: issuerAta } = await createMintWithExtensions(
+      program,
+      issuer,
+      6,
+      {}
+    ));
+    recipientAtas = [];
+    for (const r of recipients) {
+      recipientAtas.push(
+        await createAssociatedTokenAccountIdempotent(
+          provider.connection,
+          issuer,
+          mint,
+          r.publicKey,
+          {},
+          TOKEN_2022_PROGRAM_ID
+        )
+      );
+    }
+  });
+
+  it("batch_mint_tokens: mints to every destination in one instruction", async () => {
+    await program.methods
+      .batchMintTokens([new BN(10), new BN(20), new BN(30)])
+      .accountsStrict({
+        mint,
+        mintConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_config"), mint.toBuffer()],
+          program.programId
+        )[0],
+        authority: issuer.publicKey,
+        minter: PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("minter"),
+            mint.toBuffer(),
+            issuer.publicKey.toBuffer(),
+          ],
+          program.programId
+        )[0],
+        mintAuthority: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_authority"), mint.toBuffer()],
+          program.programId
+        )[0],
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(asRemaining(recipientAtas))
+      .signers([issuer])
+      .rpc();
+
+    expect(await balanceOf(recipientAtas[0])).to.eql(BigInt(10));
+    expect(await balanceOf(recipientAtas[1])).to.eql(BigInt(20));
+    expect(await balanceOf(recipientAtas[2])).to.eql(BigInt(30));
+  });
+
+  it("batch_transfer: pays every destination from one source", async () => {
+    await mintTokensTo(program, mint, issuer, issuerAta, new BN(100));
+
+    await program.methods
+      .batchTransfer([new BN(1), new BN(2), new BN(3)])
+      .accountsStrict({
+        owner: issuer.publicKey,
+        mint,
+        source: issuerAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(asRemaining(recipientAtas))
+      .signers([issuer])
+      .rpc();
+
+    expect(await balanceOf(issuerAta)).to.eql(BigInt(94));
+    expect(await balanceOf(recipientAtas[2])).to.eql(BigInt(33));
+  });
+
+  it("batch_transfer: rejects a destination of another mint", async () => {
+    const { ata: otherAta } = await createMintWithExtensions(
+      program,
+      issuer,
+      6,
+      {},
+      1
+    );
+
+    try {
+      await program.methods
+        .batchTransfer([new BN(1), new BN(1)])
+        .accountsStrict({
+          owner: issuer.publicKey,
+          mint,
+          source: issuerAta,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .remainingAccounts(asRemaining([recipientAtas[0], otherAta]))
+        .signers([issuer])
+        .rpc();
+      assert.fail("Expected a foreign-mint destination to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/InvalidDestinationMint/);
+    }
+  });
+});
+
+describe("confidential_transfer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  // Proofs generated by `cargo run --example confidential_fixtures`
+  const fixture = JSON.parse(
+    readFileSync(join(__dirname, "fixtures", "confidential.json"), "utf8")
+  );
+  const ZK_TOKEN_PROOF_PROGRAM_ID = new PublicKey(
+    "ZkTokenProof1111111111111111111111111111111"
+  );
+  const proofIx = (data: number[]) =>
+    new TransactionInstruction({
+      programId: ZK_TOKEN_PROOF_PROGRAM_ID,
+      keys: [],
+      data: Buffer.from(data),
+    });
+
+  const issuer = Keypair.generate();
+  let mint: PublicKey;
+  let ata: PublicKey;
+
+  const publicBalance = async () =>
+    (
+      await getAccount(
+        provider.connection,
+        ata,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).amount;
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey);
+    ({ mint, ata } = await createMintWithExtensions(program, issuer, 6, {
+      confidentialTransfer: {
+        autoApproveNewAccounts: true,
+        auditorElgamalPubkey: fixture.auditorElgamalPubkey,
+      },
+    }));
+    await mintTokensTo(
+      program,
+      mint,
+      issuer,
+      ata,
+      new BN(fixture.depositAmount)
+    );
+  });
+
+  it("create_mint_account: adds the ConfidentialTransferMint extension", async () => {
+    const mintInfo = await getMint(
+      provider.connection,
+      mint,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(getExtensionTypes(mintInfo.tlvData)).to.include(
+      ExtensionType.ConfidentialTransferMint
+    );
+  });
+
+  it("configure_confidential_account: verifies the pubkey validity proof", async () => {
+    await program.methods
+      .configureConfidentialAccount(
+        fixture.decryptableZeroBalance,
+        new BN(65_536),
+        1
+      )
+      .accountsStrict({
+        owner: issuer.publicKey,
+        mint,
+        tokenAccount: ata,
+        proofAccount: SYSVAR_INSTRUCTIONS_PUBKEY,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .postInstructions([proofIx(fixture.pubkeyValidityProofIx)])
+      .signers([issuer])
+      .rpc();
+
+    const acct = await getAccount(
+      provider.connection,
+      ata,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(getExtensionTypes(acct.tlvData)).to.include(
+      ExtensionType.ConfidentialTransferAccount
+    );
+  });
+
+  it("deposit_confidential and apply_pending_balance: move the public balance into the confidential one", async () => {
+    await program.methods
+      .depositConfidential(new BN(fixture.depositAmount))
+      .accountsStrict({
+        owner: issuer.publicKey,
+        mint,
+        tokenAccount: ata,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+    expect(await publicBalance()).to.eql(BigInt(0));
+
+    await program.methods
+      .applyPendingBalance(new BN(1), fixture.decryptableAfterDeposit)
+      .accountsStrict({
+        owner: issuer.publicKey,
+        tokenAccount: ata,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+  });
+
+  it("withdraw_confidential: verifies the withdraw proof and restores the public balance", async () => {
+    await program.methods
+      .withdrawConfidential(
+        new BN(fixture.withdrawAmount),
+        fixture.decryptableAfterWithdraw,
+        1
+      )
+      .accountsStrict({
+        owner: issuer.publicKey,
+        mint,
+        tokenAccount: ata,
+        proofAccount: SYSVAR_INSTRUCTIONS_PUBKEY,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .postInstructions([proofIx(fixture.withdrawProofIx)])
+      .signers([issuer])
+      .rpc();
+
+    expect(await publicBalance()).to.eql(BigInt(fixture.withdrawAmount));
+  });
+
+  it("create_mint_account: rejects confidential transfers combined with transfer fees", async () => {
+    try {
+      await createMintWithExtensions(
+        program,
+        issuer,
+        6,
+        {
+          transferFee: { transferFeeBasisPoints: 50, maximumFee: new BN(1) },
+          confidentialTransfer: {
+            autoApproveNewAccounts: true,
+            auditorElgamalPubkey: null,
+          },
+        },
+        1
+      );
+      assert.fail("Expected confidential transfers with fees to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/TransferFeeNotSupported/);
+    }
+  });
+});
+
+describe("token_group", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const issuer = Keypair.generate();
+  let group: PublicKey;
+  const members: PublicKey[] = [];
+
+  const pda = (seed: string, mint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from(seed), mint.toBuffer()],
+      program.programId
+    )[0];
+
+  const addMember = (member: PublicKey) =>
+    program.methods
+      .addGroupMember()
+      .accountsStrict({
+        authority: issuer.publicKey,
+        memberMint: member,
+        memberMintConfig: pda("mint_config", member),
+        memberMintAuthority: pda("mint_authority", member),
+        groupMint: group,
+        groupUpdateAuthority: issuer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey);
+    ({ mint: group } = await createMintWithExtensions(program, issuer, 0, {
+      groupPointer: true,
+    }));
+    for (const nonce of [1, 2, 3]) {
+      const { mint } = await createMintWithExtensions(
+        program,
+        issuer,
+        6,
+        { groupMemberPointer: true },
+        nonce
+      );
+      members.push(mint);
+    }
+  });
+
+  it("create_group: initializes the group with a max size", async () => {
+    await program.methods
+      .createGroup(2)
+      .accountsStrict({
+        authority: issuer.publicKey,
+        mint: group,
+        mintConfig: pda("mint_config", group),
+        mintAuthority: pda("mint_authority", group),
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+
+    const mintInfo = await getMint(
+      provider.connection,
+      group,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const data = getTokenGroupState(mintInfo);
+    expect(Number(data.maxSize)).to.eql(2);
+    expect(Number(data.size)).to.eql(0);
+  });
+
+  it("add_group_member: registers members and increments the group size", async () => {
+    await addMember(members[0]);
+    await addMember(members[1]);
+
+    const groupInfo = await getMint(
+      provider.connection,
+      group,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(Number(getTokenGroupState(groupInfo).size)).to.eql(2);
+
+    const memberInfo = await getMint(
+      provider.connection,
+      members[1],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const member = getTokenGroupMemberState(memberInfo);
+    expect(member.group.toBase58()).to.eql(group.toBase58());
+    expect(Number(member.memberNumber)).to.eql(2);
+  });
+
+  it("add_group_member: rejects members beyond the max size", async () => {
+    try {
+      await addMember(members[2]);
+      assert.fail("Expected a full group to reject new members");
+    } catch (err: any) {
+      expect(String(err)).to.match(/size|exceed|custom program error/i);
+    }
+  });
+});
+
+describe("close_mint", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+  const hookProgram = anchor.workspace.SplHook as Program<SplHook>;
+
+  const issuer = Keypair.generate();
+  const blocked = Keypair.generate().publicKey;
+  const rentDestination = Keypair.generate().publicKey;
+  let mint: PublicKey;
+  let ata: PublicKey;
+
+  const pda = (seed: string, key: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from(seed), key.toBuffer()],
+      program.programId
+    )[0];
+  const hookPda = (seed: string, key: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from(seed), key.toBuffer()],
+      HOOK_PROGRAM_ID
+    )[0];
+  const blocklistPage = () =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("blocklist"), mint.toBuffer(), blocked.toBuffer().subarray(0, 1)],
+      HOOK_PROGRAM_ID
+    )[0];
+  const counterPda = () =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("velocity_counter"), mint.toBuffer(), issuer.publicKey.toBuffer()],
+      HOOK_PROGRAM_ID
+    )[0];
+  const minterPda = () =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("minter"), mint.toBuffer(), issuer.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+  const closeMint = (hookAccounts = [blocklistPage(), counterPda()]) =>
+    program.methods
+      .closeMint()
+      .accountsStrict({
+        authority: issuer.publicKey,
+        destination: rentDestination,
+        mint,
+        mintConfig: pda("mint_config", mint),
+        mintRegistry: pda("mint_registry", issuer.publicKey),
+        extraMetasAccount: hookPda("extra-account-metas", mint),
+        approveAccount: hookPda("approve-account", mint),
+        mintPolicy: hookPda("mint_policy", mint),
+        velocityConfig: hookPda("velocity_config", mint),
+        kycConfig: pda("kyc_config", mint),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        hookProgram: HOOK_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: minterPda(), isSigner: false, isWritable: true },
+        ...hookAccounts.map((pubkey) => ({
+          pubkey,
+          isSigner: false,
+          isWritable: true,
+        })),
+      ])
+      .signers([issuer])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey);
+    ({ mint, ata } = await createMintWithExtensions(program, issuer, 6, {
+      transferHook: true,
+    }));
+    await mintTokensTo(program, mint, issuer, ata, new BN(5));
+
+    // Give the mint a policy with one blocklist page and velocity limits with one counter so
+    // close_mint has to close all of them
+    await hookProgram.methods
+      .initializePolicy()
+      .accountsStrict({
+        authority: issuer.publicKey,
+        mint,
+        approveAccount: hookPda("approve-account", mint),
+        policy: hookPda("mint_policy", mint),
+        extraMetasAccount: hookPda("extra-account-metas", mint),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+    await hookProgram.methods
+      .blockWallet()
+      .accountsStrict({
+        authority: issuer.publicKey,
+        mint,
+        policy: hookPda("mint_policy", mint),
+        wallet: blocked,
+        page: blocklistPage(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+    await hookProgram.methods
+      .createVelocityConfig(new BN(86_400), new BN(0))
+      .accountsStrict({
+        authority: issuer.publicKey,
+        mint,
+        approveAccount: hookPda("approve-account", mint),
+        velocityConfig: hookPda("velocity_config", mint),
+        extraMetasAccount: hookPda("extra-account-metas", mint),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+    await hookProgram.methods
+      .openVelocityCounter()
+      .accountsStrict({
+        payer: issuer.publicKey,
+        mint,
+        velocityConfig: hookPda("velocity_config", mint),
+        owner: issuer.publicKey,
+        counter: counterPda(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+  });
+
+  it("close_mint: rejects a mint with outstanding supply", async () => {
+    try {
+      await closeMint();
+      assert.fail("Expected a mint with supply to stay open");
+    } catch (err: any) {
+      expect(String(err)).to.match(/NonZeroSupply/);
+    }
+  });
+
+  it("close_mint: closes the mint and its PDAs and refunds the rent", async () => {
+    await program.methods
+      .burnTokens(new BN(5))
+      .accountsStrict({
+        mint,
+        from: ata,
+        authority: issuer.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+
+    try {
+      await closeMint([]);
+      assert.fail("Expected the blocklist page to be required");
+    } catch (err: any) {
+      expect(String(err)).to.match(/BlocklistPagesMissing/);
+    }
+    try {
+      await closeMint([blocklistPage()]);
+      assert.fail("Expected the velocity counter to be required");
+    } catch (err: any) {
+      expect(String(err)).to.match(/CountersMissing/);
+    }
+
+    await closeMint();
+
+    for (const closed of [
+      mint,
+      pda("mint_config", mint),
+      hookPda("extra-account-metas", mint),
+      hookPda("approve-account", mint),
+      hookPda("mint_policy", mint),
+      blocklistPage(),
+      hookPda("velocity_config", mint),
+      counterPda(),
+      minterPda(),
+    ]) {
+      expect(await provider.connection.getAccountInfo(closed)).to.be.null;
+    }
+    const registry = await program.account.mintRegistry.fetch(
+      pda("mint_registry", issuer.publicKey)
+    );
+    expect(registry.mints.map((m) => m.toBase58())).to.not.include(
+      mint.toBase58()
+    );
+    const refunded = await provider.connection.getBalance(rentDestination);
+    expect(refunded).to.be.greaterThan(0);
+  });
+
+  it("close_mint: the rotated close authority can close the mint", async () => {
+    const newCloseAuthority = Keypair.generate();
+    await airdrop(provider, newCloseAuthority.publicKey, 1);
+    const { mint: rotated } = await createMintWithExtensions(
+      program,
+      issuer,
+      6,
+      {},
+      1
+    );
+    await program.methods
+      .setMintAuthority({ closeMint: {} }, newCloseAuthority.publicKey)
+      .accountsStrict({
+        currentAuthority: issuer.publicKey,
+        mint: rotated,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+
+    await program.methods
+      .closeMint()
+      .accountsStrict({
+        authority: newCloseAuthority.publicKey,
+        destination: rentDestination,
+        mint: rotated,
+        mintConfig: pda("mint_config", rotated),
+        // The creator's registry, not the signer's
+        mintRegistry: pda("mint_registry", issuer.publicKey),
+        extraMetasAccount: hookPda("extra-account-metas", rotated),
+        approveAccount: hookPda("approve-account", rotated),
+        mintPolicy: hookPda("mint_policy", rotated),
+        velocityConfig: hookPda("velocity_config", rotated),
+        kycConfig: pda("kyc_config", rotated),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        hookProgram: HOOK_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        {
+          pubkey: PublicKey.findProgramAddressSync(
+            [Buffer.from("minter"), rotated.toBuffer(), issuer.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          isSigner: false,
+          isWritable: true,
+        },
+      ])
+      .signers([newCloseAuthority])
+      .rpc();
+
+    expect(await provider.connection.getAccountInfo(rotated)).to.be.null;
+    const registry = await program.account.mintRegistry.fetch(
+      pda("mint_registry", issuer.publicKey)
+    );
+    expect(registry.mints.map((m) => m.toBase58())).to.not.include(
+      rotated.toBase58()
+    );
+  });
+});
+
+describe("account_extensions", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const holder = Keypair.generate();
+  const delegate = Keypair.generate();
+  let mint: PublicKey;
+  let ata: PublicKey;
+
+  const accounts = () => ({
+    owner: holder.publicKey,
+    tokenAccount: ata,
+    systemProgram: anchor.web3.SystemProgram.programId,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+  });
+
+  const lockCpi = async () =>
+    getCpiGuard(
+      await getAccount(provider.connection, ata, "confirmed", TOKEN_2022_PROGRAM_ID)
+    )?.lockCpi;
+
+  before(async () => {
+    await airdrop(provider, holder.publicKey);
+    ({ mint, ata } = await createMintWithExtensions(program, holder, 6, {}));
+    await mintTokensTo(program, mint, holder, ata, new BN(10));
+  });
+
+  it("reallocate_token_account: rejects an empty extension list", async () => {
+    try {
+      await program.methods
+        .reallocateTokenAccount([])
+        .accountsStrict(accounts())
+        .signers([holder])
+        .rpc();
+      assert.fail("Expected an empty reallocation to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/NoExtensions/);
+    }
+  });
+
+  it("setCpiGuard: reallocates and enables the cpi guard", async () => {
+    await setCpiGuard(program, holder, ata, true);
+    expect(await lockCpi()).to.eql(true);
+
+    // Owner-signed burns through the program are now blocked
+    try {
+      await program.methods
+        .burnTokens(new BN(1))
+        .accountsStrict({
+          mint,
+          from: ata,
+          authority: holder.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([holder])
+        .rpc();
+      assert.fail("Expected the cpi guard to block the burn");
+    } catch (err: any) {
+      expect(String(err)).to.match(/custom program error/);
+    }
+  });
+
+  it("setCpiGuard: blocks delegated transfers through the program", async () => {
+    const [allowance] = PublicKey.findProgramAddressSync(
+      [Buffer.from("delegate_allowance"), ata.toBuffer()],
+      program.programId
+    );
+    const delegateAta = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      holder,
+      mint,
+      delegate.publicKey,
+      {},
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // The owner's approval is a CPI, which the guard rejects
+    try {
+      await program.methods
+        .approveDelegate(new BN(5), new BN(0))
+        .accountsStrict({
+          owner: holder.publicKey,
+          tokenAccount: ata,
+          delegate: delegate.publicKey,
+          allowance,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([holder])
+        .rpc();
+      assert.fail("Expected the cpi guard to block the approval");
+    } catch (err: any) {
+      expect(String(err)).to.match(/custom program error/);
+    }
+
+    // So the delegate has nothing to spend through
+    try {
+      await program.methods
+        .delegatedTransfer(new BN(1))
+        .accountsStrict({
+          delegate: delegate.publicKey,
+          mint,
+          source: ata,
+          destination: delegateAta,
+          allowance,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([delegate])
+        .rpc();
+      assert.fail("Expected the delegated transfer to fail");
+    } catch (err: any) {
+      expect(String(err)).to.match(/AccountNotInitialized/);
+    }
+    const holderAcct = await getAccount(
+      provider.connection,
+      ata,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(holderAcct.amount).to.eql(BigInt(10));
+  });
+
+  it("setCpiGuard: disables the cpi guard again", async () => {
+    await setCpiGuard(program, holder, ata, false);
+    expect(await lockCpi()).to.eql(false);
+
+    await program.methods
+      .burnTokens(new BN(1))
+      .accountsStrict({
+        mint,
+        from: ata,
+        authority: holder.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([holder])
+      .rpc();
+  });
+
+  it("enable_memo_transfer / disable_memo_transfer: toggles required memos", async () => {
+    await program.methods
+      .enableMemoTransfer()
+      .accountsStrict(accounts())
+      .signers([holder])
+      .rpc();
+    let account = await getAccount(
+      provider.connection,
+      ata,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(getMemoTransfer(account)?.requireIncomingTransferMemos).to.eql(
+      true
+    );
+    expect(getExtensionTypes(account.tlvData)).to.include(
+      ExtensionType.MemoTransfer
+    );
+
+    await program.methods
+      .disableMemoTransfer()
+      .accountsStrict(accounts())
+      .signers([holder])
+      .rpc();
+    account = await getAccount(
+      provider.connection,
+      ata,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(getMemoTransfer(account)?.requireIncomingTransferMemos).to.eql(
+      false
+    );
+  });
+});
+
+describe("delegation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const owner = Keypair.generate();
+  const merchant = Keypair.generate();
+  let mint: PublicKey;
+  let ata: PublicKey;
+  let merchantAta: PublicKey;
+  let allowance: PublicKey;
+
+  const approveDelegate = (amount: BN, expiresAt: BN) =>
+    program.methods
+      .approveDelegate(amount, expiresAt)
+      .accountsStrict({
+        owner: owner.publicKey,
+        tokenAccount: ata,
+        delegate: merchant.publicKey,
+        allowance,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+  const pull = (amount: BN) =>
+    program.methods
+      .delegatedTransfer(amount)
+      .accountsStrict({
+        delegate: merchant.publicKey,
+        mint,
+        source: ata,
+        destination: merchantAta,
+        allowance,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([merchant])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider, owner.publicKey);
+    await airdrop(provider, merchant.publicKey);
+    ({ mint, ata } = await createMintWithExtensions(program, owner, 6, {}));
+    await mintTokensTo(program, mint, owner, ata, new BN(100));
+    merchantAta = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      merchant,
+      mint,
+      merchant.publicKey,
+      {},
+      TOKEN_2022_PROGRAM_ID
+    );
+    [allowance] = PublicKey.findProgramAddressSync(
+      [Buffer.from("delegate_allowance"), ata.toBuffer()],
+      program.programId
+    );
+  });
+
+  it("approve_delegate: rejects an expiry in the past", async () => {
+    try {
+      await approveDelegate(new BN(10), new BN(1));
+      assert.fail("Expected a past expiry to be rejected");
+    } catch (err: any) {
+      expect(String(err)).to.match(/InvalidExpiry/);
+    }
+  });
+
+  it("delegated_transfer: pulls payments within the allowance", async () => {
+    const expiresAt = new BN(Math.floor(Date.now() / 1000) + 3600);
+    await approveDelegate(new BN(30), expiresAt);
+
+    const account = await getAccount(
+      provider.connection,
+      ata,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(account.delegate?.toBase58()).to.eql(allowance.toBase58());
+
+    await pull(new BN(10));
+    await pull(new BN(10));
+
+    const state = await program.account.delegateAllowance.fetch(allowance);
+    expect(state.remainingAmount.toNumber()).to.eql(10);
+    const received = await getAccount(
+      provider.connection,
+      merchantAta,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(Number(received.amount)).to.eql(20);
+
+    try {
+      await pull(new BN(11));
+      assert.fail("Expected the pull to exceed the allowance");
+    } catch (err: any) {
+      expect(String(err)).to.match(/AllowanceExceeded/);
+    }
+  });
+
+  it("delegated_burn: burns within the allowance", async () => {
+    await program.methods
+      .delegatedBurn(new BN(5))
+      .accountsStrict({
+        delegate: merchant.publicKey,
+        mint,
+        source: ata,
+        allowance,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([merchant])
+      .rpc();
+
+    const account = await getAccount(
+      provider.connection,
+      ata,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(Number(account.amount)).to.eql(75);
+  });
+
+  it("revoke_delegate: clears the delegate and closes the allowance", async () => {
+    await program.methods
+      .revokeDelegate()
+      .accountsStrict({
+        owner: owner.publicKey,
+        tokenAccount: ata,
+        allowance,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    const account = await getAccount(
+      provider.connection,
+      ata,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(account.delegate).to.be.null;
+    expect(await provider.connection.getAccountInfo(allowance)).to.be.null;
+  });
+});
+
+describe("extension_report", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const issuer = Keypair.generate();
+  const delegate = Keypair.generate().publicKey;
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey);
+  });
+
+  it("check_mint_extensions_constraints: reports authorities, hook program and metadata", async () => {
+    const { mint } = await createMintWithExtensions(program, issuer, 6, {
+      transferHook: true,
+      permanentDelegate: delegate,
+      transferFee: { transferFeeBasisPoints: 50, maximumFee: new BN(1000) },
+    });
+
+    const report = await program.methods
+      .checkMintExtensionsConstraints()
+      .accountsStrict({ mint })
+      .view();
+
+    expect(report.mint.toBase58()).to.eql(mint.toBase58());
+    expect(report.tokenProgram.toBase58()).to.eql(
+      TOKEN_2022_PROGRAM_ID.toBase58()
+    );
+    const find = (name: string) =>
+      report.extensions.find((extension: any) => name in extension)?.[name];
+
+    expect(find("permanentDelegate").delegate.toBase58()).to.eql(
+      delegate.toBase58()
+    );
+    expect(find("transferHook").programId.toBase58()).to.eql(
+      HOOK_PROGRAM_ID.toBase58()
+    );
+    expect(
+      find("transferFeeConfig").newerTransferFee.transferFeeBasisPoints
+    ).to.eql(50);
+    expect(find("mintCloseAuthority").closeAuthority.toBase58()).to.eql(
+      issuer.publicKey.toBase58()
+    );
+    expect(find("tokenMetadata").name).to.eql("Extension Token");
+    expect(find("nonTransferable")).to.be.undefined;
+  });
+});
+
+describe("mint_policy", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+  const hookProgram = anchor.workspace.SplHook as Program<SplHook>;
+
+  const admin = Keypair.generate();
+  const recipient = Keypair.generate();
+  const decimals = 6;
+  let mint: PublicKey;
+  let ata: PublicKey;
+  let recipientAta: PublicKey;
+  let policy: PublicKey;
+
+  const blocklistPage = (wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("blocklist"), mint.toBuffer(), wallet.toBuffer().subarray(0, 1)],
+      HOOK_PROGRAM_ID
+    )[0];
+
+  // Transfer through Token-2022, resolving the policy and blocklist pages from the meta list
+  const hookedTransfer = async (amount: bigint) => {
+    const ix = await createTransferCheckedWithTransferHookInstruction(
+      provider.connection,
+      ata,
+      mint,
+      recipientAta,
+      admin.publicKey,
+      amount,
+      decimals,
+      [],
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    return sendAndConfirmTransaction(
+      provider.connection,
+      new Transaction().add(ix),
+      [admin]
+    );
+  };
+
+  const expectRejected = async (pattern: RegExp) => {
+    try {
+      await hookedTransfer(BigInt(1));
+      assert.fail("Expected the hook to reject the transfer");
+    } catch (err: any) {
+      expect(String(err.logs ?? err)).to.match(pattern);
+    }
+  };
+
+  const setPaused = (paused: boolean) =>
+    hookProgram.methods
+      .setPaused(paused)
+      .accountsStrict({ authority: admin.publicKey, mint, policy })
+      .signers([admin])
+      .rpc();
+
+  const blocklistAccounts = (wallet: PublicKey) => ({
+    authority: admin.publicKey,
+    mint,
+    policy,
+    wallet,
+    page: blocklistPage(wallet),
+  });
+
+  before(async () => {
+    await airdrop(provider, admin.publicKey);
+    ({ mint, ata } = await createMintWithExtensions(program, admin, decimals, {
+      transferHook: true,
+    }));
+    await mintTokensTo(program, mint, admin, ata, new BN(100 * 10 ** decimals));
+    recipientAta = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      admin,
+      mint,
+      recipient.publicKey,
+      {},
+      TOKEN_2022_PROGRAM_ID
+    );
+    [policy] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_policy"), mint.toBuffer()],
+      HOOK_PROGRAM_ID
+    );
+  });
+
+  it("initialize_policy: adds the policy accounts to the hook's meta list", async () => {
+    await hookProgram.methods
+      .initializePolicy()
+      .accountsStrict({
+        authority: admin.publicKey,
+        mint,
+        approveAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from("approve-account"), mint.toBuffer()],
+          HOOK_PROGRAM_ID
+        )[0],
+        policy,
+        extraMetasAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from("extra-account-metas"), mint.toBuffer()],
+          HOOK_PROGRAM_ID
+        )[0],
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+
+    const state = await hookProgram.account.mintPolicy.fetch(policy);
+    expect(state.paused).to.eql(false);
+    // Transfers keep working with no blocklist pages created yet
+    await hookedTransfer(BigInt(10 ** decimals));
+  });
+
+  it("set_paused: halts and resumes every transfer", async () => {
+    await setPaused(true);
+    await expectRejected(/TransfersPaused/);
+
+    await setPaused(false);
+    await hookedTransfer(BigInt(10 ** decimals));
+  });
+
+  it("block_wallet: rejects transfers to a blocklisted owner", async () => {
+    await hookProgram.methods
+      .blockWallet()
+      .accountsStrict({
+        ...blocklistAccounts(recipient.publicKey),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+
+    const page = await hookProgram.account.blocklistPage.fetch(
+      blocklistPage(recipient.publicKey)
+    );
+    expect(page.owners.map((owner) => owner.toBase58())).to.eql([
+      recipient.publicKey.toBase58(),
+    ]);
+    await expectRejected(/DestinationBlocked/);
+  });
+
+  it("unblock_wallet: lets the owner receive again", async () => {
+    await hookProgram.methods
+      .unblockWallet()
+      .accountsStrict(blocklistAccounts(recipient.publicKey))
+      .signers([admin])
+      .rpc();
+
+    await hookedTransfer(BigInt(10 ** decimals));
+    const state = await hookProgram.account.mintPolicy.fetch(policy);
+    expect(state.blockedCount.toNumber()).to.eql(0);
+  });
+});
+
+describe("velocity_limits", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+  const hookProgram = anchor.workspace.SplHook as Program<SplHook>;
+
+  const admin = Keypair.generate();
+  const recipient = Keypair.generate();
+  const decimals = 6;
+  const tokens = (amount: number) => new BN(amount * 10 ** decimals);
+  let mint: PublicKey;
+  let ata: PublicKey;
+  let recipientAta: PublicKey;
+  let velocityConfig: PublicKey;
+  let counter: PublicKey;
+
+  // Transfer through Token-2022, resolving the config and the sender's counter from the meta list
+  const hookedTransfer = async (amount: BN) => {
+    const ix = await createTransferCheckedWithTransferHookInstruction(
+      provider.connection,
+      ata,
+      mint,
+      recipientAta,
+      admin.publicKey,
+      BigInt(amount.toString()),
+      decimals,
+      [],
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    return sendAndConfirmTransaction(
+      provider.connection,
+      new Transaction().add(ix),
+      [admin]
+    );
+  };
+
+  const expectRejected = async (amount: BN, pattern: RegExp) => {
+    try {
+      await hookedTransfer(amount);
+      assert.fail("Expected the hook to reject the transfer");
+    } catch (err: any) {
+      expect(String(err.logs ?? err)).to.match(pattern);
+    }
+  };
+
+  const setWalletVelocity = (tier: number, exempt: boolean) =>
+    hookProgram.methods
+      .setWalletVelocity(tier, exempt)
+      .accountsStrict({
+        authority: admin.publicKey,
+        mint,
+        velocityConfig,
+        owner: admin.publicKey,
+        counter,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider, admin.publicKey);
+    ({ mint, ata } = await createMintWithExtensions(program, admin, decimals, {
+      transferHook: true,
+    }));
+    await mintTokensTo(program, mint, admin, ata, tokens(1_000));
+    recipientAta = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      admin,
+      mint,
+      recipient.publicKey,
+      {},
+      TOKEN_2022_PROGRAM_ID
+    );
+    [velocityConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("velocity_config"), mint.toBuffer()],
+      HOOK_PROGRAM_ID
+    );
+    [counter] = PublicKey.findProgramAddressSync(
+      [Buffer.from("velocity_counter"), mint.toBuffer(), admin.publicKey.toBuffer()],
+      HOOK_PROGRAM_ID
+    );
+  });
+
+  it("create_velocity_config: senders without a counter cannot split past the limit", async () => {
+    await hookProgram.methods
+      .createVelocityConfig(new BN(86_400), tokens(10))
+      .accountsStrict({
+        authority: admin.publicKey,
+        mint,
+        approveAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from("approve-account"), mint.toBuffer()],
+          HOOK_PROGRAM_ID
+        )[0],
+        velocityConfig,
+        extraMetasAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from("extra-account-metas"), mint.toBuffer()],
+          HOOK_PROGRAM_ID
+        )[0],
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+
+    // Each transfer is under the limit, together they exceed it; without a counter the
+    // outflow cannot be recorded, so neither gets through
+    await expectRejected(tokens(6), /CounterMissing/);
+    await expectRejected(tokens(6), /CounterMissing/);
+
+    const received = await getAccount(
+      provider.connection,
+      recipientAta,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(received.amount).to.eql(BigInt(0));
+  });
+
+  it("open_velocity_counter: enforces the daily outflow limit", async () => {
+    await hookProgram.methods
+      .openVelocityCounter()
+      .accountsStrict({
+        payer: admin.publicKey,
+        mint,
+        velocityConfig,
+        owner: admin.publicKey,
+        counter,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+
+    await hookedTransfer(tokens(6));
+    await expectRejected(tokens(5), /VelocityLimitExceeded/);
+
+    const state = await hookProgram.account.velocityCounter.fetch(counter);
+    expect(state.currentAmount.toString()).to.eql(tokens(6).toString());
+  });
+
+  it("set_velocity_limits / set_wallet_velocity: a higher tier raises the owner's limit", async () => {
+    await hookProgram.methods
+      .setVelocityLimits(new BN(86_400), tokens(10), [tokens(100)])
+      .accountsStrict({ authority: admin.publicKey, mint, velocityConfig })
+      .signers([admin])
+      .rpc();
+    await setWalletVelocity(1, false);
+
+    await hookedTransfer(tokens(50));
+    await expectRejected(tokens(50), /VelocityLimitExceeded/);
+  });
+
+  it("set_wallet_velocity: exempt owners are never limited", async () => {
+    await setWalletVelocity(0, true);
+    await hookedTransfer(tokens(200));
+
+    const state = await hookProgram.account.velocityCounter.fetch(counter);
+    expect(state.exempt).to.eql(true);
+    expect(state.currentAmount.toString()).to.eql(tokens(56).toString());
+  });
+});
+
+describe("token_events", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  const issuer = Keypair.generate();
+  const stranger = Keypair.generate();
+  let mint: PublicKey;
+  let ata: PublicKey;
+
+  // Events emitted by a confirmed transaction, parsed from its logs
+  const eventsOf = async (signature: string) => {
+    await provider.connection.confirmTransaction(signature, "confirmed");
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    return Array.from(parser.parseLogs(tx?.meta?.logMessages ?? []));
+  };
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey);
+    await airdrop(provider, stranger.publicKey);
+    ({ mint, ata } = await createMintWithExtensions(program, issuer, 6, {}));
+  });
+
+  it("mint_tokens and burn_tokens: report the new supply", async () => {
+    await mintTokensTo(program, mint, issuer, ata, new BN(100));
+
+    const signature = await program.methods
+      .burnTokens(new BN(40))
+      .accountsStrict({
+        mint,
+        from: ata,
+        authority: issuer.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([issuer])
+      .rpc();
+
+    const [burned] = await eventsOf(signature);
+    expect(burned.name).to.eql("tokensBurned");
+    expect(burned.data.amount.toNumber()).to.eql(40);
+    expect(burned.data.supply.toNumber()).to.eql(60);
+  });
+
+  it("burn_tokens: rejects a signer that is neither owner nor delegate", async () => {
+    try {
+      await program.methods
+        .burnTokens(new BN(1))
+        .accountsStrict({
+          mint,
+          from: ata,
+          authority: stranger.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Expected the burn to be rejected");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eql("AuthorityMismatch");
+    }
+  });
+
+  it("freeze_token_account: emits AccountFrozen and rejects other signers", async () => {
+    try {
+      await program.methods
+        .freezeTokenAccount()
+        .accountsStrict({
+          account: ata,
+          mint,
+          freezeAuthority: stranger.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Expected the freeze to be rejected");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eql("AuthorityMismatch");
+    }
+
+    const accounts = {
+      account: ata,
+      mint,
+      freezeAuthority: issuer.publicKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+    const frozen = await program.methods
+      .freezeTokenAccount()
+      .accountsStrict(accounts)
+      .signers([issuer])
+      .rpc();
+    expect((await eventsOf(frozen))[0].name).to.eql("accountFrozen");
+
+    const thawed = await program.methods
+      .thawTokenAccount()
+      .accountsStrict({
+        ...accounts,
+        kycConfig: PublicKey.findProgramAddressSync(
+          [Buffer.from("kyc_config"), mint.toBuffer()],
+          program.programId
+        )[0],
+      })
+      .signers([issuer])
+      .rpc();
+    expect((await eventsOf(thawed))[0].name).to.eql("accountThawed");
+  });
+
+  it("close_token_account: rejects a non-empty account", async () => {
+    try {
+      await program.methods
+        .closeTokenAccount()
+        .accountsStrict({
+          account: ata,
+          destination: issuer.publicKey,
+          authority: issuer.publicKey,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([issuer])
+        .rpc();
+      assert.fail("Expected the close to be rejected");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.eql("NonEmptyAccount");
+    }
+  });
+});
+
+describe("hooked_transfers", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Spl as Program<Spl>;
+
+  // Every wrapper that moves tokens must forward the hook accounts it is given
+  const issuer = Keypair.generate();
+  const holder = Keypair.generate();
+  const decimals = 6;
+  let mint: PublicKey;
+  let issuerAta: PublicKey;
+  let holderAta: PublicKey;
+
+  const resolve = (
+    source: PublicKey,
+    destination: PublicKey,
+    owner: PublicKey,
+    amount: BN
+  ) =>
+    hookAccounts(provider.connection, mint, source, destination, owner, amount);
+
+  const balanceOf = async (ata: PublicKey) =>
+    (
+      await getAccount(
+        provider.connection,
+        ata,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).amount;
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 5);
+    await airdrop(provider, holder.publicKey);
+    ({ mint, ata: issuerAta } = await createMintWithExtensions(
+      program,
+      issuer,
+      decimals,
+      { transferHook: true, permanentDelegate: issuer.publicKey }
+    ));
+    holderAta = await createAssociatedTokenAccountIdempotent(
+      provider.connection,
+      issuer,
+      mint,
+      holder.publicKey,
+      {},
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTokensTo(program, mint, issuer, issuerAta, new BN(10_000));
+    await mintTokensTo(program, mint, issuer, holderAta, new BN(1_000));
+  });
+
+  it("clawback / force_transfer: move hooked tokens as the permanent delegate", async () => {
+    await program.methods
+      .clawback(new BN(100), 1)
+      .accountsStrict({
+        delegate: issuer.publicKey,
+        mint,
+        source: holderAta,
+        destination: issuerAta,
+        freezeAuthority: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        await resolve(holderAta, issuerAta, issuer.publicKey, new BN(100))
+      )
+      .signers([issuer])
+      .rpc();
+    expect(await balanceOf(holderAta)).to.eql(BigInt(900));
+
+    await program.methods
+      .forceTransfer(new BN(100), 2)
+      .accountsStrict({
+        delegate: issuer.publicKey,
+        mint,
+        source: issuerAta,
+        destination: holderAta,
+        freezeAuthority: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        await resolve(issuerAta, holderAta, issuer.publicKey, new BN(100))
+      )
+      .signers([issuer])
+      .rpc();
+    expect(await balanceOf(holderAta)).to.eql(BigInt(1_000));
+  });
+
+  it("clawback: fails when the hook accounts are not forwarded", async () => {
+    let threw = false;
+    try {
+      await program.methods
+        .clawback(new BN(1), 1)
+        .accountsStrict({
+          delegate: issuer.publicKey,
+          mint,
+          source: holderAta,
+          destination: issuerAta,
+          freezeAuthority: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([issuer])
+        .rpc();
+    } catch {
+      threw = true;
+    }
+    expect(threw).to.be.true;
+  });
+
+  it("batch_transfer: hook accounts follow the destinations", async () => {
+    await program.methods
+      .batchTransfer([new BN(5)])
+      .accountsStrict({
+        owner: issuer.publicKey,
+        mint,
+        source: issuerAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: holderAta, isSigner: false, isWritable: true },
+        ...(await resolve(issuerAta, holderAta, issuer.publicKey, new BN(5))),
+      ])
+      .signers([issuer])
+      .rpc();
+    expect(await balanceOf(holderAta)).to.eql(BigInt(1_005));
+  });
+
+  it("create_vesting / release_vested / revoke_vesting: fund and pay out a hooked vault", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const vestingAddress = (id: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("vesting"),
+          mint.toBuffer(),
+          holder.publicKey.toBuffer(),
+          new BN(id).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+    const createVesting = async (id: number, schedule: Record<string, unknown>) => {
+      const vesting = vestingAddress(id);
+      const vault = associatedAddress({ mint, owner: vesting });
+      await program.methods
+        .createVesting(new BN(id), new BN(200), schedule as any)
+        .accountsStrict({
+          grantor: issuer.publicKey,
+          beneficiary: holder.publicKey,
+          mint,
+          grantorTokenAccount: issuerAta,
+          vesting,
+          vault,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        })
+        .remainingAccounts(
+          await resolve(issuerAta, vault, issuer.publicKey, new BN(200))
+        )
+        .signers([issuer])
+        .rpc();
+      return { vesting, vault };
+    };
+
+    const vested = await createVesting(0, {
+      startTime: new BN(now - 200),
+      cliffTime: new BN(now - 100),
+      endTime: new BN(now - 10),
+      period: new BN(10),
+      revocable: false,
+    });
+    const before = await balanceOf(holderAta);
+    await program.methods
+      .releaseVested()
+      .accountsStrict({
+        beneficiary: holder.publicKey,
+        mint,
+        vesting: vested.vesting,
+        vault: vested.vault,
+        beneficiaryTokenAccount: holderAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        await resolve(vested.vault, holderAta, vested.vesting, new BN(200))
+      )
+      .signers([holder])
+      .rpc();
+    expect(await balanceOf(holderAta)).to.eql(before + BigInt(200));
+
+    const unvested = await createVesting(1, {
+      startTime: new BN(now),
+      cliffTime: new BN(now + 1_000),
+      endTime: new BN(now + 2_000),
+      period: new BN(100),
+      revocable: true,
+    });
+    await program.methods
+      .revokeVesting()
+      .accountsStrict({
+        grantor: issuer.publicKey,
+        mint,
+        vesting: unvested.vesting,
+        vault: unvested.vault,
+        grantorTokenAccount: issuerAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        await resolve(unvested.vault, issuerAta, unvested.vesting, new BN(200))
+      )
+      .signers([issuer])
+      .rpc();
+    expect(await balanceOf(unvested.vault)).to.eql(BigInt(0));
+  });
+
+  it("create_distributor / claim_airdrop: fund and claim from a hooked vault", async () => {
+    const amount = new BN(50);
+    const { root } = airdropTree([{ claimant: holder.publicKey, amount }]);
+    const [distributor] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("distributor"),
+        mint.toBuffer(),
+        issuer.publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const vault = associatedAddress({ mint, owner: distributor });
+    await program.methods
+      .createDistributor(
+        new BN(0),
+        root,
+        1,
+        amount,
+        new BN(Math.floor(Date.now() / 1000) - 10),
+        new BN(Math.floor(Date.now() / 1000) + 3_600)
+      )
+      .accountsStrict({
+        admin: issuer.publicKey,
+        mint,
+        adminTokenAccount: issuerAta,
+        distributor,
+        vault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        await resolve(issuerAta, vault, issuer.publicKey, amount)
+      )
+      .signers([issuer])
+      .rpc();
+
+    const before = await balanceOf(holderAta);
+    await program.methods
+      .claimAirdrop(0, amount, [])
+      .accountsStrict({
+        claimant: holder.publicKey,
+        mint,
+        distributor,
+        vault,
+        claimantTokenAccount: holderAta,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(await resolve(vault, holderAta, distributor, amount))
+      .signers([holder])
+      .rpc();
+    expect(await balanceOf(holderAta)).to.eql(before + BigInt(50));
+  });
+
+  it("delegated_transfer: pulls a hooked payment within the allowance", async () => {
+    const [allowance] = PublicKey.findProgramAddressSync(
+      [Buffer.from("delegate_allowance"), holderAta.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .approveDelegate(
+        new BN(30),
+        new BN(Math.floor(Date.now() / 1000) + 3_600)
+      )
+      .accountsStrict({
+        owner: holder.publicKey,
+        tokenAccount: holderAta,
+        delegate: issuer.publicKey,
+        allowance,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([holder])
+      .rpc();
+
+    const before = await balanceOf(issuerAta);
+    await program.methods
+      .delegatedTransfer(new BN(10))
+      .accountsStrict({
+        delegate: issuer.publicKey,
+        mint,
+        source: holderAta,
+        destination: issuerAta,
+        allowance,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        await resolve(holderAta, issuerAta, allowance, new BN(10))
+      )
+      .signers([issuer])
+      .rpc();
+    expect(await balanceOf(issuerAta)).to.eql(before + BigInt(10));
+  });
+});
@@ -25,6 +25,11 @@ const TOKEN_2022_ID = new PublicKey(
   "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
 );
 
+// Transfer hook program ID
+const HOOK_PROGRAM_ID = new PublicKey(
+  "BTHMQqp22RzFimuNydEbebbgMnVERJiVxdsFbmZDNg8W"
+);
+
 // Seeds for PDAs
 const POOL_SEED = Buffer.from("staking_pool");
 const USER_STAKE_SEED = Buffer.from("user_stake");
@@ -52,7 +57,11 @@ describe("staking_program", () => {
   before("derive PDAs", async () => {
     // Derive mint PDA
     [mintPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("mint"), payer.publicKey.toBuffer()],
+      [
+        Buffer.from("mint"),
+        payer.publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
       program.programId
     );
 
@@ -333,3 +342,239 @@ describe("staking_program", () => {
     expect(new BN(userStakeAcc.amountStaked).isZero()).to.be.true;
   });
 });
+
+describe("staking_program with a transfer-hooked mint", () => {
+  const staker = anchor.web3.Keypair.generate();
+  let hookedMint: PublicKey,
+    hookedPool: PublicKey,
+    hookedVault: PublicKey,
+    stakerAta: PublicKey,
+    stakerStake: PublicKey;
+
+  // Accounts Token-2022 needs to invoke the hook of `hookedMint`
+  const hookMetas = () =>
+    [
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("extra-account-metas"), hookedMint.toBuffer()],
+        HOOK_PROGRAM_ID
+      )[0],
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("approve-account"), hookedMint.toBuffer()],
+        HOOK_PROGRAM_ID
+      )[0],
+      HOOK_PROGRAM_ID,
+    ].map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }));
+
+  before("create a hooked mint and its pool", async () => {
+    const sig = await provider.connection.requestAirdrop(
+      staker.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(sig, "confirmed");
+
+    [hookedMint] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("mint"),
+        staker.publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    [hookedPool] = PublicKey.findProgramAddressSync(
+      [POOL_SEED, hookedMint.toBuffer()],
+      program.programId
+    );
+    hookedVault = getAssociatedTokenAddressSync(
+      hookedMint,
+      hookedPool,
+      true,
+      TOKEN_2022_ID,
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    stakerAta = getAssociatedTokenAddressSync(
+      hookedMint,
+      staker.publicKey,
+      false,
+      TOKEN_2022_ID,
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    [stakerStake] = PublicKey.findProgramAddressSync(
+      [USER_STAKE_SEED, hookedPool.toBuffer(), staker.publicKey.toBuffer()],
+      program.programId
+    );
+    const mintConfig = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_config"), hookedMint.toBuffer()],
+      program.programId
+    )[0];
+    const mintAuthority = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_authority"), hookedMint.toBuffer()],
+      program.programId
+    )[0];
+    const minter = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("minter"),
+        hookedMint.toBuffer(),
+        staker.publicKey.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+    await program.methods
+      .createMintAccount(
+        9,
+        "Hooked Stake",
+        "HSTK",
+        "",
+        {
+          transferHook: true,
+          transferFee: null,
+          interestRate: null,
+          nonTransferable: false,
+          permanentDelegate: null,
+          defaultFrozen: false,
+          confidentialTransfer: null,
+          groupPointer: false,
+          groupMemberPointer: false,
+        } as any,
+        new BN(0),
+        null
+      )
+      .accountsStrict({
+        payer: staker.publicKey,
+        authority: staker.publicKey,
+        receiver: staker.publicKey,
+        mint: hookedMint,
+        mintRegistry: PublicKey.findProgramAddressSync(
+          [Buffer.from("mint_registry"), staker.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mintConfig,
+        mintAuthority,
+        mintTokenAccount: stakerAta,
+        approveAccount: hookMetas()[1].pubkey,
+        extraMetasAccount: hookMetas()[0].pubkey,
+        systemProgram: SystemProgram.programId,
+        hookProgram: HOOK_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_ID,
+      })
+      .signers([staker])
+      .rpc();
+
+    await program.methods
+      .addMinter(new BN("18446744073709551615"), new BN(0), true)
+      .accountsStrict({
+        authority: staker.publicKey,
+        mint: hookedMint,
+        mintConfig,
+        minterAuthority: staker.publicKey,
+        minter,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([staker])
+      .rpc();
+    await program.methods
+      .mintTokens(new BN(1_000).mul(PRECISION))
+      .accountsStrict({
+        mint: hookedMint,
+        mintConfig,
+        to: stakerAta,
+        authority: staker.publicKey,
+        minter,
+        mintAuthority,
+        tokenProgram: TOKEN_2022_ID,
+      })
+      .signers([staker])
+      .rpc();
+
+    // No reward emission, so unstaking returns exactly the principal
+    await program.methods
+      .initializePoolStake(new BN(0))
+      .accountsStrict({
+        admin: staker.publicKey,
+        stakeMint: hookedMint,
+        rewardMint: hookedMint,
+        pool: hookedPool,
+        stakeVault: hookedVault,
+        rewardVault: hookedVault,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([staker])
+      .rpc();
+  });
+
+  it("stakes and unstakes through the hook", async () => {
+    const stakeAmt = new BN(10).mul(PRECISION);
+    await program.methods
+      .stake(stakeAmt)
+      .accountsStrict({
+        staker: staker.publicKey,
+        stakeMint: hookedMint,
+        pool: hookedPool,
+        stakeVault: hookedVault,
+        userStakeAccount: stakerAta,
+        userStake: stakerStake,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .remainingAccounts(hookMetas())
+      .signers([staker])
+      .rpc();
+    expect(await bal(hookedVault)).to.be.a.bignumber.equal(stakeAmt);
+
+    const beforeUnstake = await bal(stakerAta);
+    await program.methods
+      .unstake(stakeAmt)
+      .accountsStrict({
+        staker: staker.publicKey,
+        stakeMint: hookedMint,
+        rewardMint: hookedMint,
+        pool: hookedPool,
+        stakeVault: hookedVault,
+        userStakeAccount: stakerAta,
+        userStake: stakerStake,
+        rewardVault: hookedVault,
+        userRewardAccount: stakerAta,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .remainingAccounts(hookMetas())
+      .signers([staker])
+      .rpc();
+    expect((await bal(stakerAta)).sub(beforeUnstake)).to.be.a.bignumber.equal(
+      stakeAmt
+    );
+  });
+
+  it("rejects a stake that omits the hook accounts", async () => {
+    let threw = false;
+    try {
+      await program.methods
+        .stake(new BN(1))
+        .accountsStrict({
+          staker: staker.publicKey,
+          stakeMint: hookedMint,
+          pool: hookedPool,
+          stakeVault: hookedVault,
+          userStakeAccount: stakerAta,
+          userStake: stakerStake,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([staker])
+        .rpc();
+    } catch {
+      threw = true;
+    }
+    expect(threw).to.be.true;
+  });
+});